@@ -0,0 +1,538 @@
+//! Active speaker detection from the RFC 6464 audio-level header extension.
+//!
+//! [`ActiveSpeakerObserver`] watches the RFC 6464 `ssrc-audio-level` header extension carried by
+//! every monitored producer's RTP packets and, on a periodic evaluation tick, decides which
+//! producer is the current dominant speaker using a log-likelihood-ratio activity score computed
+//! over three sliding windows (immediate/medium/long). [`ActiveSpeakerObserver::on_dominant_speaker`]
+//! fires only when that decision actually changes, with hysteresis to avoid flapping between two
+//! similarly loud speakers.
+
+use crate::data_structures::AppData;
+use crate::producer::{Producer, ProducerId};
+use crate::router::rtp_observer::{RtpObserver, RtpObserverAddProducerOptions, RtpObserverId};
+use crate::worker::RequestError;
+use async_trait::async_trait;
+use event_listener_primitives::{Bag, BagOnce, HandlerId};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Number of packets the immediate window covers, roughly 200ms at a 20ms packetization time.
+const IMMEDIATE_WINDOW_PACKETS: usize = 10;
+/// Number of packets the medium window covers, roughly 1.5s at a 20ms packetization time.
+const MEDIUM_WINDOW_PACKETS: usize = 75;
+/// Number of packets the long window covers, roughly 5s at a 20ms packetization time.
+const LONG_WINDOW_PACKETS: usize = 250;
+
+/// How many equal-width buckets an RFC 6464 level (0-127, lower is louder) is quantized into
+/// before scoring.
+const ACTIVITY_BINS: usize = 8;
+const BIN_WIDTH: u8 = (128 / ACTIVITY_BINS as u32) as u8;
+
+/// Reference bin distribution for speech, biased toward the loud end (low bin indices).
+const SPEECH_DISTRIBUTION: [f64; ACTIVITY_BINS] = [0.36, 0.26, 0.16, 0.10, 0.06, 0.03, 0.02, 0.01];
+/// Reference bin distribution for silence/background noise, biased toward the quiet end.
+const SILENCE_DISTRIBUTION: [f64; ACTIVITY_BINS] = [0.02, 0.03, 0.05, 0.08, 0.12, 0.18, 0.24, 0.28];
+
+/// Relative weight each window contributes to a producer's combined activity score.
+const IMMEDIATE_WEIGHT: f64 = 0.5;
+const MEDIUM_WEIGHT: f64 = 0.35;
+const LONG_WEIGHT: f64 = 0.15;
+
+/// How much higher a challenger's score must be than the current dominant speaker's before it's
+/// even considered for a handover.
+const DOMINANCE_MARGIN: f64 = 4.0;
+/// How many consecutive evaluation ticks a challenger must keep winning before the handover
+/// actually happens, damping flapping between two similarly loud speakers.
+const DOMINANCE_HOLD_TICKS: u8 = 3;
+
+fn quantize(level: u8) -> usize {
+    usize::from(level / BIN_WIDTH).min(ACTIVITY_BINS - 1)
+}
+
+/// Log-likelihood ratio of `window`'s bin distribution under the speech vs. silence reference
+/// distributions: positive and larger means more speech-like.
+fn interval_score(window: &VecDeque<u8>) -> f64 {
+    if window.is_empty() {
+        return 0.0;
+    }
+
+    let mut counts = [0usize; ACTIVITY_BINS];
+    for &level in window {
+        counts[quantize(level)] += 1;
+    }
+
+    counts
+        .iter()
+        .enumerate()
+        .map(|(bin, &count)| {
+            count as f64 * (SPEECH_DISTRIBUTION[bin] / SILENCE_DISTRIBUTION[bin]).ln()
+        })
+        .sum()
+}
+
+fn push_bounded(window: &mut VecDeque<u8>, level: u8, capacity: usize) {
+    window.push_back(level);
+    while window.len() > capacity {
+        window.pop_front();
+    }
+}
+
+#[derive(Debug, Default)]
+struct LevelHistory {
+    immediate: VecDeque<u8>,
+    medium: VecDeque<u8>,
+    long: VecDeque<u8>,
+}
+
+impl LevelHistory {
+    fn push(&mut self, level: u8) {
+        push_bounded(&mut self.immediate, level, IMMEDIATE_WINDOW_PACKETS);
+        push_bounded(&mut self.medium, level, MEDIUM_WINDOW_PACKETS);
+        push_bounded(&mut self.long, level, LONG_WINDOW_PACKETS);
+    }
+
+    fn score(&self) -> f64 {
+        IMMEDIATE_WEIGHT * interval_score(&self.immediate)
+            + MEDIUM_WEIGHT * interval_score(&self.medium)
+            + LONG_WEIGHT * interval_score(&self.long)
+    }
+}
+
+/// Sliding-window dominant-speaker detector, decoupled from the observer's worker-facing
+/// lifecycle so the scoring logic can be exercised directly.
+#[derive(Debug, Default)]
+struct DominantSpeakerDetector {
+    histories: HashMap<ProducerId, LevelHistory>,
+    dominant: Option<ProducerId>,
+    challenger: Option<(ProducerId, u8)>,
+}
+
+impl DominantSpeakerDetector {
+    fn add_producer(&mut self, producer_id: ProducerId) {
+        self.histories.entry(producer_id).or_default();
+    }
+
+    fn remove_producer(&mut self, producer_id: ProducerId) {
+        self.histories.remove(&producer_id);
+        if self.dominant == Some(producer_id) {
+            self.dominant = None;
+        }
+        if matches!(self.challenger, Some((id, _)) if id == producer_id) {
+            self.challenger = None;
+        }
+    }
+
+    fn observe_audio_level(&mut self, producer_id: ProducerId, level: u8) {
+        if let Some(history) = self.histories.get_mut(&producer_id) {
+            history.push(level);
+        }
+    }
+
+    /// Re-scores every monitored producer and returns `Some(producer_id)` exactly when the
+    /// dominant speaker changes.
+    fn evaluate(&mut self) -> Option<ProducerId> {
+        let (leader, leader_score) = self
+            .histories
+            .iter()
+            .map(|(&producer_id, history)| (producer_id, history.score()))
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())?;
+
+        if Some(leader) == self.dominant {
+            self.challenger = None;
+            return None;
+        }
+
+        let dominant_score = self
+            .dominant
+            .and_then(|dominant| self.histories.get(&dominant))
+            .map(LevelHistory::score)
+            .unwrap_or(f64::NEG_INFINITY);
+
+        if leader_score < dominant_score + DOMINANCE_MARGIN {
+            self.challenger = None;
+            return None;
+        }
+
+        match self.challenger {
+            Some((challenger, ticks)) if challenger == leader => {
+                if ticks + 1 >= DOMINANCE_HOLD_TICKS {
+                    self.challenger = None;
+                    self.dominant = Some(leader);
+                    Some(leader)
+                } else {
+                    self.challenger = Some((leader, ticks + 1));
+                    None
+                }
+            }
+            _ => {
+                self.challenger = Some((leader, 1));
+                None
+            }
+        }
+    }
+}
+
+/// Options for creating an [`ActiveSpeakerObserver`], see
+/// [`Router::create_active_speaker_observer`](crate::router::Router::create_active_speaker_observer).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ActiveSpeakerObserverOptions {
+    /// Interval in ms between two consecutive dominant speaker evaluations.
+    pub interval: u16,
+    /// Custom application data.
+    pub app_data: AppData,
+}
+
+impl Default for ActiveSpeakerObserverOptions {
+    fn default() -> Self {
+        Self {
+            interval: 300,
+            app_data: AppData::default(),
+        }
+    }
+}
+
+impl ActiveSpeakerObserverOptions {
+    /// * `interval` - Interval in ms between two consecutive dominant speaker evaluations.
+    pub fn new(interval: u16) -> Self {
+        Self {
+            interval,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Default)]
+struct Handlers {
+    pause: Bag<Arc<dyn Fn() + Send + Sync>>,
+    resume: Bag<Arc<dyn Fn() + Send + Sync>>,
+    add_producer: Bag<Arc<dyn Fn(&Producer) + Send + Sync>>,
+    remove_producer: Bag<Arc<dyn Fn(&Producer) + Send + Sync>>,
+    dominant_speaker: Bag<Arc<dyn Fn(&ProducerId) + Send + Sync>>,
+    router_close: BagOnce<Box<dyn FnOnce() + Send>>,
+    close: BagOnce<Box<dyn FnOnce() + Send>>,
+}
+
+/// Resolves a [`ProducerId`] monitored by this observer's router into the live [`Producer`] it
+/// names, so `on_add_producer`/`on_remove_producer` listeners can be handed the same handle the
+/// router itself hands out elsewhere. Supplied by the router at construction time, since this
+/// observer has no producer registry of its own.
+type GetProducer = Arc<dyn Fn(ProducerId) -> Option<Producer> + Send + Sync>;
+
+struct Inner {
+    id: RtpObserverId,
+    interval: u16,
+    app_data: AppData,
+    paused: AtomicBool,
+    closed: AtomicBool,
+    producer_ids: Mutex<HashSet<ProducerId>>,
+    detector: Mutex<DominantSpeakerDetector>,
+    get_producer: GetProducer,
+    handlers: Handlers,
+}
+
+/// An RTP observer that monitors a set of audio producers and reports which one is currently the
+/// dominant speaker, based on RFC 6464 audio levels. See the [module docs](self).
+#[derive(Clone)]
+pub struct ActiveSpeakerObserver {
+    inner: Arc<Inner>,
+}
+
+impl ActiveSpeakerObserver {
+    /// `get_producer` resolves a monitored [`ProducerId`] to its [`Producer`] handle, so
+    /// `on_add_producer`/`on_remove_producer` listeners receive it; the router supplies this from
+    /// its own producer registry.
+    pub(crate) fn new(
+        id: RtpObserverId,
+        options: ActiveSpeakerObserverOptions,
+        get_producer: GetProducer,
+    ) -> Self {
+        let observer = Self {
+            inner: Arc::new(Inner {
+                id,
+                interval: options.interval,
+                app_data: options.app_data,
+                paused: AtomicBool::new(false),
+                closed: AtomicBool::new(false),
+                producer_ids: Mutex::new(HashSet::new()),
+                detector: Mutex::new(DominantSpeakerDetector::default()),
+                get_producer,
+                handlers: Handlers::default(),
+            }),
+        };
+
+        crate::observer::observer().emit_new_rtp_observer(
+            crate::observer::NewRtpObserver::ActiveSpeaker(observer.clone()),
+        );
+
+        observer
+    }
+
+    /// Interval in ms between two consecutive dominant speaker evaluations.
+    pub fn interval(&self) -> u16 {
+        self.inner.interval
+    }
+
+    /// Feeds one RTP packet's RFC 6464 audio level (0-127, lower is louder) for `producer_id`
+    /// into the detector. Called by the router for every audio packet received from a monitored
+    /// producer; a no-op for producers that aren't currently monitored, or while paused/closed.
+    pub(crate) fn observe_audio_level(&self, producer_id: ProducerId, level: u8) {
+        if self.inner.paused.load(Ordering::SeqCst) || self.inner.closed.load(Ordering::SeqCst) {
+            return;
+        }
+        self.inner
+            .detector
+            .lock()
+            .unwrap()
+            .observe_audio_level(producer_id, level);
+    }
+
+    /// Re-evaluates the dominant speaker across all monitored producers; called once per
+    /// [`ActiveSpeakerObserver::interval`] by the router's evaluation timer. Fires
+    /// [`ActiveSpeakerObserver::on_dominant_speaker`] only when the dominant producer actually
+    /// changes.
+    pub(crate) fn evaluate(&self) {
+        if self.inner.paused.load(Ordering::SeqCst) || self.inner.closed.load(Ordering::SeqCst) {
+            return;
+        }
+
+        if let Some(dominant_producer_id) = self.inner.detector.lock().unwrap().evaluate() {
+            self.inner
+                .handlers
+                .dominant_speaker
+                .call(|callback| callback(&dominant_producer_id));
+        }
+    }
+
+    /// Callback invoked whenever the dominant speaker changes, with the now-dominant producer's
+    /// id.
+    pub fn on_dominant_speaker<F: Fn(&ProducerId) + Send + Sync + 'static>(
+        &self,
+        callback: F,
+    ) -> HandlerId {
+        self.inner.handlers.dominant_speaker.add(Arc::new(callback))
+    }
+}
+
+#[async_trait(?Send)]
+impl RtpObserver for ActiveSpeakerObserver {
+    fn id(&self) -> RtpObserverId {
+        self.inner.id
+    }
+
+    fn paused(&self) -> bool {
+        self.inner.paused.load(Ordering::SeqCst)
+    }
+
+    fn app_data(&self) -> &AppData {
+        &self.inner.app_data
+    }
+
+    fn closed(&self) -> bool {
+        self.inner.closed.load(Ordering::SeqCst)
+    }
+
+    async fn pause(&self) -> Result<(), RequestError> {
+        self.inner.paused.store(true, Ordering::SeqCst);
+        self.inner.handlers.pause.call_simple();
+        Ok(())
+    }
+
+    async fn resume(&self) -> Result<(), RequestError> {
+        self.inner.paused.store(false, Ordering::SeqCst);
+        self.inner.handlers.resume.call_simple();
+        Ok(())
+    }
+
+    async fn add_producer(
+        &self,
+        rtp_observer_add_producer_options: RtpObserverAddProducerOptions,
+    ) -> Result<(), RequestError> {
+        let producer_id = rtp_observer_add_producer_options.producer_id;
+        self.inner.producer_ids.lock().unwrap().insert(producer_id);
+        self.inner
+            .detector
+            .lock()
+            .unwrap()
+            .add_producer(producer_id);
+        if let Some(producer) = (self.inner.get_producer)(producer_id) {
+            self.inner
+                .handlers
+                .add_producer
+                .call(|callback| callback(&producer));
+        }
+        Ok(())
+    }
+
+    async fn remove_producer(&self, producer_id: ProducerId) -> Result<(), RequestError> {
+        self.inner.producer_ids.lock().unwrap().remove(&producer_id);
+        self.inner
+            .detector
+            .lock()
+            .unwrap()
+            .remove_producer(producer_id);
+        if let Some(producer) = (self.inner.get_producer)(producer_id) {
+            self.inner
+                .handlers
+                .remove_producer
+                .call(|callback| callback(&producer));
+        }
+        Ok(())
+    }
+
+    /// Overrides the default per-item loop: every id in the batch is folded into
+    /// `producer_ids`/`detector` under one lock acquisition each instead of one pair of
+    /// acquisitions per producer, then `on_add_producer` fires once per id as usual.
+    async fn add_producers(
+        &self,
+        rtp_observer_add_producer_options: impl IntoIterator<Item = RtpObserverAddProducerOptions>,
+    ) -> Result<(), RequestError> {
+        let producer_ids: Vec<ProducerId> = rtp_observer_add_producer_options
+            .into_iter()
+            .map(|options| options.producer_id)
+            .collect();
+
+        {
+            let mut ids = self.inner.producer_ids.lock().unwrap();
+            let mut detector = self.inner.detector.lock().unwrap();
+            for &producer_id in &producer_ids {
+                ids.insert(producer_id);
+                detector.add_producer(producer_id);
+            }
+        }
+
+        for producer_id in producer_ids {
+            if let Some(producer) = (self.inner.get_producer)(producer_id) {
+                self.inner
+                    .handlers
+                    .add_producer
+                    .call(|callback| callback(&producer));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Overrides the default per-item loop the same way [`Self::add_producers`] does.
+    async fn remove_producers(
+        &self,
+        producer_ids: impl IntoIterator<Item = ProducerId> + 'async_trait,
+    ) -> Result<(), RequestError> {
+        let producer_ids: Vec<ProducerId> = producer_ids.into_iter().collect();
+
+        {
+            let mut ids = self.inner.producer_ids.lock().unwrap();
+            let mut detector = self.inner.detector.lock().unwrap();
+            for &producer_id in &producer_ids {
+                ids.remove(&producer_id);
+                detector.remove_producer(producer_id);
+            }
+        }
+
+        for producer_id in producer_ids {
+            if let Some(producer) = (self.inner.get_producer)(producer_id) {
+                self.inner
+                    .handlers
+                    .remove_producer
+                    .call(|callback| callback(&producer));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// This observer doesn't watch for producer closure itself: membership changes only through
+    /// `add_producer(s)`/`remove_producer(s)`, so a monitored producer that closes stays listed
+    /// until it is removed.
+    fn producer_ids(&self) -> Vec<ProducerId> {
+        self.inner
+            .producer_ids
+            .lock()
+            .unwrap()
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    fn on_pause<F: Fn() + Send + Sync + 'static>(&self, callback: F) -> HandlerId {
+        self.inner.handlers.pause.add(Arc::new(callback))
+    }
+
+    fn on_resume<F: Fn() + Send + Sync + 'static>(&self, callback: F) -> HandlerId {
+        self.inner.handlers.resume.add(Arc::new(callback))
+    }
+
+    fn on_add_producer<F: Fn(&Producer) + Send + Sync + 'static>(&self, callback: F) -> HandlerId {
+        self.inner.handlers.add_producer.add(Arc::new(callback))
+    }
+
+    fn on_remove_producer<F: Fn(&Producer) + Send + Sync + 'static>(
+        &self,
+        callback: F,
+    ) -> HandlerId {
+        self.inner.handlers.remove_producer.add(Arc::new(callback))
+    }
+
+    fn on_router_close<F: FnOnce() + Send + 'static>(&self, callback: F) -> HandlerId {
+        self.inner.handlers.router_close.add(Box::new(callback))
+    }
+
+    fn on_close<F: FnOnce() + Send + 'static>(&self, callback: F) -> HandlerId {
+        self.inner.handlers.close.add(Box::new(callback))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn producer_id() -> ProducerId {
+        ProducerId::default()
+    }
+
+    #[test]
+    fn dominant_speaker_switches_after_sustained_lead() {
+        let mut detector = DominantSpeakerDetector::default();
+        let quiet = producer_id();
+        let loud = producer_id();
+        detector.add_producer(quiet);
+        detector.add_producer(loud);
+
+        // `quiet` starts out marginally louder, so the opening tick registers it (not `loud`) as
+        // the challenger and `loud`'s hold below has to start from zero.
+        for _ in 0..LONG_WINDOW_PACKETS {
+            detector.observe_audio_level(quiet, 90);
+            detector.observe_audio_level(loud, 100);
+        }
+        assert_eq!(detector.evaluate(), None);
+
+        for _ in 0..LONG_WINDOW_PACKETS {
+            detector.observe_audio_level(loud, 10);
+        }
+
+        assert_eq!(detector.evaluate(), None);
+        assert_eq!(detector.evaluate(), None);
+        assert_eq!(detector.evaluate(), Some(loud));
+        // Already dominant, no further change to report.
+        assert_eq!(detector.evaluate(), None);
+    }
+
+    #[test]
+    fn dominant_speaker_cleared_when_producer_removed() {
+        let mut detector = DominantSpeakerDetector::default();
+        let loud = producer_id();
+        detector.add_producer(loud);
+        for _ in 0..LONG_WINDOW_PACKETS {
+            detector.observe_audio_level(loud, 5);
+        }
+        for _ in 0..DOMINANCE_HOLD_TICKS {
+            detector.evaluate();
+        }
+        assert_eq!(detector.dominant, Some(loud));
+
+        detector.remove_producer(loud);
+        assert_eq!(detector.dominant, None);
+        assert_eq!(detector.evaluate(), None);
+    }
+}
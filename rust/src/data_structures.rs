@@ -6,10 +6,14 @@ use serde::ser::SerializeStruct;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::any::Any;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt;
-use std::net::IpAddr;
+use std::net::{AddrParseError, IpAddr};
+use std::num::ParseIntError;
 use std::ops::{Deref, DerefMut};
+use std::str::FromStr;
 use std::sync::Arc;
+use thiserror::Error;
 
 /// Container for arbitrary data attached to Mediasoup entities.
 #[derive(Debug, Clone)]
@@ -97,19 +101,50 @@ pub enum IceCandidateType {
     Relay,
 }
 
-/// ICE candidate TCP type (always `Passive`).
+impl IceCandidateType {
+    /// Conventional RFC 8445 §5.1.2.2 type preference for this candidate type, used by
+    /// [`compute_ice_priority`] when no caller-supplied override applies. Higher values are
+    /// preferred: `126` for `Host`, `110` for `Prflx`, `100` for `Srflx`, `0` for `Relay`.
+    pub fn default_type_preference(self) -> u8 {
+        match self {
+            IceCandidateType::Host => 126,
+            IceCandidateType::Prflx => 110,
+            IceCandidateType::Srflx => 100,
+            IceCandidateType::Relay => 0,
+        }
+    }
+}
+
+/// ICE candidate TCP type. mediasoup only ever advertises `Passive`; `Active`/`So` only show up
+/// when parsing a candidate received from a remote peer (see
+/// [`IceCandidate::validate_native_transport`]).
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum IceCandidateTcpType {
+    /// This candidate accepts an incoming TCP connection.
     Passive,
+    /// This candidate initiates an outgoing TCP connection. Only meaningful for a remote
+    /// candidate; mediasoup never advertises this itself.
+    Active,
+    /// This candidate can both initiate and accept the TCP connection. Only meaningful for a
+    /// remote candidate; mediasoup never advertises this itself.
+    So,
 }
 
-/// Transport protocol.
+/// Transport protocol. mediasoup only ever advertises `UDP`/`TCP`; `TLS`/`QUIC` only show up when
+/// parsing a candidate received from a remote peer (see
+/// [`IceCandidate::validate_native_transport`]).
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TransportProtocol {
     TCP,
     UDP,
+    /// DTLS tunneled over TCP. Only meaningful for a remote candidate; mediasoup never advertises
+    /// this itself.
+    TLS,
+    /// DTLS tunneled over a QUIC datagram path. Only meaningful for a remote candidate; mediasoup
+    /// never advertises this itself.
+    QUIC,
 }
 
 /// ICE candidate
@@ -129,10 +164,355 @@ pub struct IceCandidate {
     pub port: u16,
     /// The type of candidate (always `Host`).
     pub r#type: IceCandidateType,
-    /// The type of TCP candidate (always `Passive`).
+    /// The type of TCP candidate (always `Passive` for candidates mediasoup advertises itself, see
+    /// [`IceCandidate::validate_native_transport`]).
     pub tcp_type: Option<IceCandidateTcpType>,
 }
 
+/// Default `local_preference` used by [`IceCandidate::new`] when none is supplied.
+pub const DEFAULT_ICE_LOCAL_PREFERENCE: u16 = 65535;
+/// Default `component_id` used by [`IceCandidate::new`] when none is supplied (RTP, the only
+/// component mediasoup ever describes).
+pub const DEFAULT_ICE_COMPONENT_ID: u8 = 1;
+
+/// Computes an RFC 8445 §5.1.2.1 candidate priority:
+/// `2^24 * type_preference + 2^8 * local_preference + (256 - component_id)`.
+///
+/// `type_preference` ranks candidates of different types (see
+/// [`IceCandidateType::default_type_preference`]); `local_preference` breaks ties between
+/// same-type, same-foundation candidates, which is what you need for a stable ranking when the
+/// same candidate is gathered on several transports; `component_id` is `1` for RTP.
+pub fn compute_ice_priority(type_preference: u8, local_preference: u16, component_id: u8) -> u32 {
+    2u32.pow(24) * u32::from(type_preference)
+        + 2u32.pow(8) * u32::from(local_preference)
+        + (256 - u32::from(component_id))
+}
+
+impl IceCandidate {
+    /// Creates a new candidate with its priority computed per RFC 8445 (see
+    /// [`compute_ice_priority`]), using [`DEFAULT_ICE_LOCAL_PREFERENCE`] and
+    /// [`DEFAULT_ICE_COMPONENT_ID`]. See [`IceCandidate::new_with_local_preference`] to rank
+    /// several candidates sharing a `foundation` (e.g. gathered across multiple transports).
+    pub fn new(
+        foundation: String,
+        ip: IpAddr,
+        protocol: TransportProtocol,
+        port: u16,
+        r#type: IceCandidateType,
+    ) -> Self {
+        Self::new_with_local_preference(
+            foundation,
+            ip,
+            protocol,
+            port,
+            r#type,
+            DEFAULT_ICE_LOCAL_PREFERENCE,
+        )
+    }
+
+    /// Like [`IceCandidate::new`], but with an explicit `local_preference`. See
+    /// [`IceCandidate::new_with_local_preference_and_component_id`] to also override the
+    /// component id.
+    pub fn new_with_local_preference(
+        foundation: String,
+        ip: IpAddr,
+        protocol: TransportProtocol,
+        port: u16,
+        r#type: IceCandidateType,
+        local_preference: u16,
+    ) -> Self {
+        Self::new_with_local_preference_and_component_id(
+            foundation,
+            ip,
+            protocol,
+            port,
+            r#type,
+            local_preference,
+            DEFAULT_ICE_COMPONENT_ID,
+        )
+    }
+
+    /// Like [`IceCandidate::new_with_local_preference`], but with an explicit `component_id`.
+    pub fn new_with_local_preference_and_component_id(
+        foundation: String,
+        ip: IpAddr,
+        protocol: TransportProtocol,
+        port: u16,
+        r#type: IceCandidateType,
+        local_preference: u16,
+        component_id: u8,
+    ) -> Self {
+        let priority = compute_ice_priority(
+            r#type.default_type_preference(),
+            local_preference,
+            component_id,
+        );
+        Self {
+            foundation,
+            priority,
+            ip,
+            protocol,
+            port,
+            r#type,
+            tcp_type: None,
+        }
+    }
+
+    /// Recomputes and overwrites [`IceCandidate::priority`] for a new `local_preference`, keeping
+    /// this candidate's type preference and [`DEFAULT_ICE_COMPONENT_ID`]. Use this to re-rank a
+    /// candidate once its `foundation` turns out to also appear on other transports.
+    pub fn set_priority_with_local_preference(&mut self, local_preference: u16) {
+        self.priority = compute_ice_priority(
+            self.r#type.default_type_preference(),
+            local_preference,
+            DEFAULT_ICE_COMPONENT_ID,
+        );
+    }
+
+    /// Checks that this candidate only uses the transport/tcptype combinations mediasoup itself
+    /// ever advertises (`udp`/`tcp` and, for TCP, `tcptype passive`). A candidate parsed from a
+    /// remote peer may legitimately use the richer set (`tls`/`quic`, `tcptype active`/`so`) and
+    /// shouldn't be checked this way; this is for validating candidates *this* side constructs to
+    /// send out.
+    pub fn validate_native_transport(&self) -> Result<(), NativeTransportError> {
+        if !matches!(
+            self.protocol,
+            TransportProtocol::TCP | TransportProtocol::UDP
+        ) {
+            return Err(NativeTransportError::UnsupportedProtocol(self.protocol));
+        }
+        if let Some(tcp_type) = self.tcp_type {
+            if tcp_type != IceCandidateTcpType::Passive {
+                return Err(NativeTransportError::UnsupportedTcpType(tcp_type));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Error that caused [`IceCandidate::validate_native_transport`] to fail.
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum NativeTransportError {
+    /// mediasoup only ever advertises `udp`/`tcp`.
+    #[error("mediasoup only ever advertises udp/tcp candidates, not {0:?}")]
+    UnsupportedProtocol(TransportProtocol),
+    /// mediasoup only ever advertises `tcptype passive`.
+    #[error("mediasoup only ever advertises tcptype passive, not {0:?}")]
+    UnsupportedTcpType(IceCandidateTcpType),
+}
+
+fn ice_candidate_type_as_str(candidate_type: IceCandidateType) -> &'static str {
+    match candidate_type {
+        IceCandidateType::Host => "host",
+        IceCandidateType::Srflx => "srflx",
+        IceCandidateType::Prflx => "prflx",
+        IceCandidateType::Relay => "relay",
+    }
+}
+
+fn ice_candidate_type_from_str(s: &str) -> Option<IceCandidateType> {
+    match s {
+        "host" => Some(IceCandidateType::Host),
+        "srflx" => Some(IceCandidateType::Srflx),
+        "prflx" => Some(IceCandidateType::Prflx),
+        "relay" => Some(IceCandidateType::Relay),
+        _ => None,
+    }
+}
+
+fn transport_protocol_as_str(protocol: TransportProtocol) -> &'static str {
+    match protocol {
+        TransportProtocol::TCP => "tcp",
+        TransportProtocol::UDP => "udp",
+        TransportProtocol::TLS => "tls",
+        TransportProtocol::QUIC => "quic",
+    }
+}
+
+fn transport_protocol_from_str(s: &str) -> Option<TransportProtocol> {
+    match s {
+        "tcp" => Some(TransportProtocol::TCP),
+        "udp" => Some(TransportProtocol::UDP),
+        "tls" => Some(TransportProtocol::TLS),
+        "quic" => Some(TransportProtocol::QUIC),
+        _ => None,
+    }
+}
+
+/// Error that caused [`IceCandidate`] parsing error, see [`IceCandidate::from_str`].
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum ParseIceCandidateError {
+    /// Input doesn't start with the `candidate:` prefix.
+    #[error("input does not start with \"candidate:\"")]
+    MissingPrefix,
+    /// Input has too few (or unexpected trailing) tokens.
+    #[error("unexpected number of tokens in candidate line")]
+    UnexpectedTokenCount,
+    /// Component id other than `1`; mediasoup only ever describes the RTP component.
+    #[error("component id must be 1 (RTP)")]
+    InvalidComponentId,
+    /// Invalid transport protocol, expected one of `udp`/`tcp`/`tls`/`quic`.
+    #[error("invalid transport protocol {0:?}")]
+    InvalidTransportProtocol(String),
+    /// Invalid priority integer.
+    #[error("invalid priority: {0}")]
+    InvalidPriority(ParseIntError),
+    /// Invalid candidate address.
+    #[error("invalid IP address: {0}")]
+    InvalidIp(AddrParseError),
+    /// Invalid port integer.
+    #[error("invalid port: {0}")]
+    InvalidPort(ParseIntError),
+    /// Missing the `typ` keyword ahead of the candidate type.
+    #[error("missing \"typ\" keyword")]
+    MissingTypeKeyword,
+    /// Invalid candidate type, expected one of `host`/`srflx`/`prflx`/`relay`.
+    #[error("invalid candidate type {0:?}")]
+    InvalidCandidateType(String),
+    /// Invalid `tcptype` value, expected one of `passive`/`active`/`so`.
+    #[error("invalid tcptype value {0:?}")]
+    InvalidTcpType(String),
+}
+
+impl fmt::Display for IceCandidate {
+    /// Formats this candidate as an RFC 8839 `a=candidate` attribute value, e.g.
+    /// `candidate:0 1 udp 2122194687 10.0.0.1 54400 typ host`. Does not include the leading
+    /// `a=candidate:` SDP line prefix, so it can be composed into a full SDP line by the caller.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "candidate:{} 1 {} {} {} {} typ {}",
+            self.foundation,
+            transport_protocol_as_str(self.protocol),
+            self.priority,
+            self.ip,
+            self.port,
+            ice_candidate_type_as_str(self.r#type),
+        )?;
+
+        if let Some(tcp_type) = self.tcp_type {
+            write!(
+                f,
+                " tcptype {}",
+                match tcp_type {
+                    IceCandidateTcpType::Passive => "passive",
+                    IceCandidateTcpType::Active => "active",
+                    IceCandidateTcpType::So => "so",
+                },
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for IceCandidate {
+    type Err = ParseIceCandidateError;
+
+    /// Parses an RFC 8839 `a=candidate` attribute value, see [`IceCandidate::fmt`]. `raddr`/
+    /// `rport` are accepted (and validated) for interop with peers that send non-host candidates,
+    /// but aren't retained: mediasoup is ICE-lite and [`IceCandidate`] has no related-address
+    /// fields to store them in.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let rest = line
+            .strip_prefix("candidate:")
+            .ok_or(ParseIceCandidateError::MissingPrefix)?;
+        let mut tokens = rest.split_whitespace();
+
+        let foundation = tokens
+            .next()
+            .ok_or(ParseIceCandidateError::UnexpectedTokenCount)?
+            .to_string();
+
+        if tokens.next() != Some("1") {
+            return Err(ParseIceCandidateError::InvalidComponentId);
+        }
+
+        let protocol_str = tokens
+            .next()
+            .ok_or(ParseIceCandidateError::UnexpectedTokenCount)?;
+        let protocol = transport_protocol_from_str(protocol_str).ok_or_else(|| {
+            ParseIceCandidateError::InvalidTransportProtocol(protocol_str.to_string())
+        })?;
+
+        let priority = tokens
+            .next()
+            .ok_or(ParseIceCandidateError::UnexpectedTokenCount)?
+            .parse()
+            .map_err(ParseIceCandidateError::InvalidPriority)?;
+
+        let ip = tokens
+            .next()
+            .ok_or(ParseIceCandidateError::UnexpectedTokenCount)?
+            .parse()
+            .map_err(ParseIceCandidateError::InvalidIp)?;
+
+        let port = tokens
+            .next()
+            .ok_or(ParseIceCandidateError::UnexpectedTokenCount)?
+            .parse()
+            .map_err(ParseIceCandidateError::InvalidPort)?;
+
+        if tokens.next() != Some("typ") {
+            return Err(ParseIceCandidateError::MissingTypeKeyword);
+        }
+
+        let type_str = tokens
+            .next()
+            .ok_or(ParseIceCandidateError::UnexpectedTokenCount)?;
+        let r#type = ice_candidate_type_from_str(type_str)
+            .ok_or_else(|| ParseIceCandidateError::InvalidCandidateType(type_str.to_string()))?;
+
+        let mut tcp_type = None;
+
+        while let Some(keyword) = tokens.next() {
+            match keyword {
+                "raddr" => {
+                    tokens
+                        .next()
+                        .ok_or(ParseIceCandidateError::UnexpectedTokenCount)?
+                        .parse::<IpAddr>()
+                        .map_err(ParseIceCandidateError::InvalidIp)?;
+                    if tokens.next() != Some("rport") {
+                        return Err(ParseIceCandidateError::UnexpectedTokenCount);
+                    }
+                    tokens
+                        .next()
+                        .ok_or(ParseIceCandidateError::UnexpectedTokenCount)?
+                        .parse::<u16>()
+                        .map_err(ParseIceCandidateError::InvalidPort)?;
+                }
+                "tcptype" => {
+                    let tcp_type_str = tokens
+                        .next()
+                        .ok_or(ParseIceCandidateError::UnexpectedTokenCount)?;
+                    tcp_type = Some(match tcp_type_str {
+                        "passive" => IceCandidateTcpType::Passive,
+                        "active" => IceCandidateTcpType::Active,
+                        "so" => IceCandidateTcpType::So,
+                        _ => {
+                            return Err(ParseIceCandidateError::InvalidTcpType(
+                                tcp_type_str.to_string(),
+                            ))
+                        }
+                    });
+                }
+                _ => return Err(ParseIceCandidateError::UnexpectedTokenCount),
+            }
+        }
+
+        Ok(IceCandidate {
+            foundation,
+            priority,
+            ip,
+            protocol,
+            port,
+            r#type,
+            tcp_type,
+        })
+    }
+}
+
 /// ICE state.
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -266,6 +646,204 @@ pub enum DtlsFingerprint {
     },
 }
 
+/// Selects which hash algorithm [`DtlsFingerprint::from_certificate_der`] hashes a certificate
+/// with.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum FingerprintAlgorithm {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+/// Constant-time byte comparison: for inputs of equal length, always walks their full length
+/// instead of returning on the first mismatch, so comparing a fingerprint doesn't leak how many
+/// leading bytes matched through response timing. Inputs of different lengths return `false`
+/// immediately.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+impl DtlsFingerprint {
+    /// Hashes `der` (a DER-encoded X.509 certificate) with `algorithm`, producing the
+    /// [`DtlsFingerprint`] mediasoup would advertise for that certificate. Compare the result
+    /// against a peer's advertised fingerprint with [`DtlsFingerprint::verify`] to confirm the
+    /// certificate presented on the wire is the one they signaled.
+    pub fn from_certificate_der(der: &[u8], algorithm: FingerprintAlgorithm) -> Self {
+        match algorithm {
+            FingerprintAlgorithm::Sha1 => {
+                use sha1::{Digest, Sha1};
+
+                let mut value = [0u8; 20];
+                value.copy_from_slice(&Sha1::digest(der));
+                DtlsFingerprint::Sha1 { value }
+            }
+            FingerprintAlgorithm::Sha224 => {
+                use sha2::{Digest, Sha224};
+
+                let mut value = [0u8; 28];
+                value.copy_from_slice(&Sha224::digest(der));
+                DtlsFingerprint::Sha224 { value }
+            }
+            FingerprintAlgorithm::Sha256 => {
+                use sha2::{Digest, Sha256};
+
+                let mut value = [0u8; 32];
+                value.copy_from_slice(&Sha256::digest(der));
+                DtlsFingerprint::Sha256 { value }
+            }
+            FingerprintAlgorithm::Sha384 => {
+                use sha2::{Digest, Sha384};
+
+                let mut value = [0u8; 48];
+                value.copy_from_slice(&Sha384::digest(der));
+                DtlsFingerprint::Sha384 { value }
+            }
+            FingerprintAlgorithm::Sha512 => {
+                use sha2::{Digest, Sha512};
+
+                let mut value = [0u8; 64];
+                value.copy_from_slice(&Sha512::digest(der));
+                DtlsFingerprint::Sha512 { value }
+            }
+        }
+    }
+
+    /// Hashes `der` with every algorithm [`FingerprintAlgorithm`] supports, in ascending strength
+    /// order (`sha-1` through `sha-512`). Convenient for populating
+    /// [`DtlsParameters::fingerprints`](crate::data_structures::DtlsParameters::fingerprints) with
+    /// one entry per algorithm so a peer can pick whichever it supports.
+    pub fn all_from_certificate_der(der: &[u8]) -> Vec<DtlsFingerprint> {
+        [
+            FingerprintAlgorithm::Sha1,
+            FingerprintAlgorithm::Sha224,
+            FingerprintAlgorithm::Sha256,
+            FingerprintAlgorithm::Sha384,
+            FingerprintAlgorithm::Sha512,
+        ]
+        .into_iter()
+        .map(|algorithm| DtlsFingerprint::from_certificate_der(der, algorithm))
+        .collect()
+    }
+
+    /// Compares this fingerprint against `other` in constant time (see [`constant_time_eq`]).
+    /// Returns `false` if the two were computed with different algorithms; use this rather than
+    /// `==` to check a peer's advertised fingerprint against the certificate they present on the
+    /// wire, since that's a security-relevant comparison and shouldn't leak timing information.
+    pub fn verify(&self, other: &DtlsFingerprint) -> bool {
+        match (self, other) {
+            (DtlsFingerprint::Sha1 { value: a }, DtlsFingerprint::Sha1 { value: b }) => {
+                constant_time_eq(a, b)
+            }
+            (DtlsFingerprint::Sha224 { value: a }, DtlsFingerprint::Sha224 { value: b }) => {
+                constant_time_eq(a, b)
+            }
+            (DtlsFingerprint::Sha256 { value: a }, DtlsFingerprint::Sha256 { value: b }) => {
+                constant_time_eq(a, b)
+            }
+            (DtlsFingerprint::Sha384 { value: a }, DtlsFingerprint::Sha384 { value: b }) => {
+                constant_time_eq(a, b)
+            }
+            (DtlsFingerprint::Sha512 { value: a }, DtlsFingerprint::Sha512 { value: b }) => {
+                constant_time_eq(a, b)
+            }
+            _ => false,
+        }
+    }
+
+    /// The algorithm this fingerprint was computed with.
+    pub fn algorithm(&self) -> FingerprintAlgorithm {
+        match self {
+            DtlsFingerprint::Sha1 { .. } => FingerprintAlgorithm::Sha1,
+            DtlsFingerprint::Sha224 { .. } => FingerprintAlgorithm::Sha224,
+            DtlsFingerprint::Sha256 { .. } => FingerprintAlgorithm::Sha256,
+            DtlsFingerprint::Sha384 { .. } => FingerprintAlgorithm::Sha384,
+            DtlsFingerprint::Sha512 { .. } => FingerprintAlgorithm::Sha512,
+        }
+    }
+
+    /// Encodes this fingerprint as a libp2p
+    /// [certhash](https://github.com/libp2p/specs/blob/master/webrtc/webrtc-direct.md) multihash:
+    /// the multihash code for SHA2-256 (`0x12`), the digest length (`32`), then the 32 raw digest
+    /// bytes. Both the code and length fit in a single varint byte, so this doesn't need a general
+    /// varint encoder. Only defined for [`DtlsFingerprint::Sha256`], since `sha-256` is the only
+    /// algorithm libp2p's certhash interop recognizes; base-encode the result (e.g. with
+    /// multibase) to build a `/certhash/<multibase>` WebRTC-direct address component.
+    pub fn to_multihash(&self) -> Result<Vec<u8>, MultihashError> {
+        match self {
+            DtlsFingerprint::Sha256 { value } => {
+                let mut multihash = Vec::with_capacity(2 + value.len());
+                multihash.push(MULTIHASH_CODE_SHA2_256);
+                multihash.push(value.len() as u8);
+                multihash.extend_from_slice(value);
+                Ok(multihash)
+            }
+            _ => Err(MultihashError::UnsupportedAlgorithm(self.algorithm())),
+        }
+    }
+
+    /// Reverses [`DtlsFingerprint::to_multihash`], validating the multihash code and length before
+    /// re-assembling a [`DtlsFingerprint::Sha256`]. The result re-serializes through the existing
+    /// [`Serialize`] impl to the same canonical upper-hex colon form any other `DtlsFingerprint`
+    /// does.
+    pub fn try_from_multihash(bytes: &[u8]) -> Result<Self, MultihashError> {
+        if bytes.len() < 2 {
+            return Err(MultihashError::Truncated {
+                expected: 2,
+                actual: bytes.len(),
+            });
+        }
+
+        let code = bytes[0];
+        if code != MULTIHASH_CODE_SHA2_256 {
+            return Err(MultihashError::UnexpectedCode(code));
+        }
+
+        let len = bytes[1];
+        if len != 32 {
+            return Err(MultihashError::UnexpectedLength(len));
+        }
+
+        let digest = &bytes[2..];
+        if digest.len() != 32 {
+            return Err(MultihashError::Truncated {
+                expected: 32,
+                actual: digest.len(),
+            });
+        }
+
+        let mut value = [0u8; 32];
+        value.copy_from_slice(digest);
+        Ok(DtlsFingerprint::Sha256 { value })
+    }
+}
+
+/// Multihash code for SHA2-256, see <https://github.com/multiformats/multicodec>.
+const MULTIHASH_CODE_SHA2_256: u8 = 0x12;
+
+/// Error produced by [`DtlsFingerprint::to_multihash`]/[`DtlsFingerprint::try_from_multihash`].
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum MultihashError {
+    /// [`DtlsFingerprint::to_multihash`] was called on a fingerprint whose algorithm isn't
+    /// `sha-256`, the only one libp2p's certhash interop defines.
+    #[error("multihash certhash interop is only defined for sha-256, not {0:?}")]
+    UnsupportedAlgorithm(FingerprintAlgorithm),
+    /// The multihash's leading code byte wasn't `0x12` (SHA2-256).
+    #[error("expected multihash code 0x12 (sha2-256), got {0:#x}")]
+    UnexpectedCode(u8),
+    /// The multihash's length byte wasn't `32`.
+    #[error("expected multihash digest length 32, got {0}")]
+    UnexpectedLength(u8),
+    /// The byte count doesn't match what the header or declared length require: either too few
+    /// bytes, or bytes left over past the declared 32-byte digest.
+    #[error("multihash has the wrong length, expected {expected} bytes, got {actual}")]
+    Truncated { expected: usize, actual: usize },
+}
+
 impl Serialize for DtlsFingerprint {
     fn serialize<S>(&self, serializer: S) -> Result<<S as Serializer>::Ok, <S as Serializer>::Error>
     where
@@ -699,6 +1277,22 @@ pub enum WebRtcMessage {
     EmptyBinary,
 }
 
+/// Error produced by [`WebRtcMessage::try_from_ppid`] or
+/// [`WebRtcMessageReassembler::accept`].
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum WebRtcMessageError {
+    /// The SCTP PPID isn't one of the values `WebRtcMessage` knows how to decode.
+    #[error("unknown SCTP PPID {0}")]
+    UnknownPpid(u32),
+    /// A string message's payload (after reassembly, if fragmented) isn't valid UTF-8.
+    #[error("payload is not valid UTF-8")]
+    InvalidUtf8,
+    /// A fragmented message (PPID 52/54) exceeded the reassembler's configured byte limit before
+    /// its final chunk arrived.
+    #[error("reassembly buffer for stream {stream_id} exceeded {limit} bytes")]
+    ReassemblyOverflow { stream_id: u16, limit: usize },
+}
+
 impl WebRtcMessage {
     // +------------------------------------+-----------+
     // | Value                              | SCTP PPID |
@@ -711,16 +1305,18 @@ impl WebRtcMessage {
     // | WebRTC Binary Empty                | 57        |
     // +------------------------------------+-----------+
 
-    pub(crate) fn new(ppid: u32, payload: Bytes) -> Self {
-        // TODO: Make this fallible instead
+    /// Decodes a complete (not fragmented) message from its SCTP PPID and payload. Doesn't handle
+    /// the deprecated partial PPIDs (52/54); route those through [`WebRtcMessageReassembler`]
+    /// first.
+    pub(crate) fn try_from_ppid(ppid: u32, payload: Bytes) -> Result<Self, WebRtcMessageError> {
         match ppid {
-            51 => WebRtcMessage::String(String::from_utf8(payload.to_vec()).unwrap()),
-            53 => WebRtcMessage::Binary(payload),
-            56 => WebRtcMessage::EmptyString,
-            57 => WebRtcMessage::EmptyBinary,
-            _ => {
-                panic!("Bad ppid {}", ppid);
-            }
+            51 => String::from_utf8(payload.to_vec())
+                .map(WebRtcMessage::String)
+                .map_err(|_| WebRtcMessageError::InvalidUtf8),
+            53 => Ok(WebRtcMessage::Binary(payload)),
+            56 => Ok(WebRtcMessage::EmptyString),
+            57 => Ok(WebRtcMessage::EmptyBinary),
+            _ => Err(WebRtcMessageError::UnknownPpid(ppid)),
         }
     }
 
@@ -734,6 +1330,88 @@ impl WebRtcMessage {
     }
 }
 
+/// Default byte limit used by [`WebRtcMessageReassembler::default`], matching the netstring
+/// reassembly cap in [`crate::worker::channels`].
+const DEFAULT_REASSEMBLY_MAX_BYTES: usize = 4194304;
+
+/// Reassembles messages fragmented with the deprecated partial PPIDs (52 `WebRTC Binary Partial`,
+/// 54 `WebRTC String Partial`), for interop with peers old enough to still send them. One instance
+/// is kept per `DirectTransport` data consumer; each SCTP stream id accumulates independently.
+///
+/// A 52/54 chunk is buffered until the matching final chunk (PPID 51/53) arrives, at which point
+/// the concatenated payload is decoded and returned; a 51/53 chunk with nothing buffered is
+/// decoded on its own. Any other PPID discards whatever is buffered for its stream and is decoded
+/// directly through [`WebRtcMessage::try_from_ppid`].
+#[derive(Debug)]
+pub(crate) struct WebRtcMessageReassembler {
+    max_buffered_bytes: usize,
+    buffers: HashMap<u16, Vec<u8>>,
+}
+
+impl WebRtcMessageReassembler {
+    /// Creates a reassembler that rejects (via
+    /// [`WebRtcMessageError::ReassemblyOverflow`]) any stream whose buffered partial payload
+    /// exceeds `max_buffered_bytes`.
+    pub(crate) fn new(max_buffered_bytes: usize) -> Self {
+        Self {
+            max_buffered_bytes,
+            buffers: HashMap::new(),
+        }
+    }
+
+    /// Feeds one SCTP chunk for `stream_id` through the reassembler. Returns `Ok(Some(message))`
+    /// once a complete message is available, `Ok(None)` while still waiting on further partial
+    /// chunks, and `Err` if the PPID/payload is invalid or the buffer limit was exceeded.
+    pub(crate) fn accept(
+        &mut self,
+        stream_id: u16,
+        ppid: u32,
+        payload: Bytes,
+    ) -> Result<Option<WebRtcMessage>, WebRtcMessageError> {
+        match ppid {
+            52 | 54 => {
+                let buffer = self.buffers.entry(stream_id).or_default();
+                buffer.extend_from_slice(&payload);
+
+                if buffer.len() > self.max_buffered_bytes {
+                    let limit = self.max_buffered_bytes;
+                    self.buffers.remove(&stream_id);
+                    return Err(WebRtcMessageError::ReassemblyOverflow { stream_id, limit });
+                }
+
+                Ok(None)
+            }
+            51 | 53 => {
+                let payload = match self.buffers.remove(&stream_id) {
+                    Some(mut buffered) => {
+                        buffered.extend_from_slice(&payload);
+                        Bytes::from(buffered)
+                    }
+                    None => payload,
+                };
+
+                WebRtcMessage::try_from_ppid(ppid, payload).map(Some)
+            }
+            _ => {
+                self.reset(stream_id);
+                WebRtcMessage::try_from_ppid(ppid, payload).map(Some)
+            }
+        }
+    }
+
+    /// Discards any partially-reassembled message buffered for `stream_id`, e.g. when the data
+    /// consumer resets the stream.
+    pub(crate) fn reset(&mut self, stream_id: u16) {
+        self.buffers.remove(&stream_id);
+    }
+}
+
+impl Default for WebRtcMessageReassembler {
+    fn default() -> Self {
+        Self::new(DEFAULT_REASSEMBLY_MAX_BYTES)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -770,4 +1448,298 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn dtls_fingerprint_from_certificate_der() {
+        let fingerprint = DtlsFingerprint::from_certificate_der(b"", FingerprintAlgorithm::Sha256);
+        match fingerprint {
+            DtlsFingerprint::Sha256 { value } => {
+                assert_eq!(
+                    value,
+                    [
+                        0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8,
+                        0x99, 0x6f, 0xb9, 0x24, 0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c,
+                        0xa4, 0x95, 0x99, 0x1b, 0x78, 0x52, 0xb8, 0x55,
+                    ],
+                );
+            }
+            other => panic!("expected Sha256, got {:?}", other),
+        }
+
+        let same = DtlsFingerprint::from_certificate_der(b"", FingerprintAlgorithm::Sha256);
+        assert!(fingerprint.verify(&same));
+
+        let different = DtlsFingerprint::from_certificate_der(b"x", FingerprintAlgorithm::Sha256);
+        assert!(!fingerprint.verify(&different));
+
+        let other_algorithm =
+            DtlsFingerprint::from_certificate_der(b"", FingerprintAlgorithm::Sha1);
+        assert!(!fingerprint.verify(&other_algorithm));
+    }
+
+    #[test]
+    fn dtls_fingerprint_multihash_round_trip() {
+        let fingerprint = DtlsFingerprint::from_certificate_der(b"", FingerprintAlgorithm::Sha256);
+        let multihash = fingerprint.to_multihash().unwrap();
+        assert_eq!(multihash[0], 0x12);
+        assert_eq!(multihash[1], 32);
+        assert_eq!(multihash.len(), 34);
+
+        let decoded = DtlsFingerprint::try_from_multihash(&multihash).unwrap();
+        assert_eq!(
+            serde_json::to_string(&decoded).unwrap(),
+            serde_json::to_string(&fingerprint).unwrap(),
+        );
+
+        assert_eq!(
+            DtlsFingerprint::try_from_multihash(&[0x11, 32]),
+            Err(MultihashError::UnexpectedCode(0x11)),
+        );
+        assert_eq!(
+            DtlsFingerprint::try_from_multihash(&[0x12, 20]),
+            Err(MultihashError::UnexpectedLength(20)),
+        );
+        assert_eq!(
+            DtlsFingerprint::try_from_multihash(&[0x12, 32, 0, 0]),
+            Err(MultihashError::Truncated {
+                expected: 32,
+                actual: 2,
+            }),
+        );
+
+        let sha1 = DtlsFingerprint::from_certificate_der(b"", FingerprintAlgorithm::Sha1);
+        assert_eq!(
+            sha1.to_multihash(),
+            Err(MultihashError::UnsupportedAlgorithm(
+                FingerprintAlgorithm::Sha1
+            )),
+        );
+    }
+
+    #[test]
+    fn dtls_fingerprint_all_from_certificate_der() {
+        let fingerprints = DtlsFingerprint::all_from_certificate_der(b"");
+        assert_eq!(
+            fingerprints
+                .iter()
+                .map(DtlsFingerprint::algorithm)
+                .collect::<Vec<_>>(),
+            vec![
+                FingerprintAlgorithm::Sha1,
+                FingerprintAlgorithm::Sha224,
+                FingerprintAlgorithm::Sha256,
+                FingerprintAlgorithm::Sha384,
+                FingerprintAlgorithm::Sha512,
+            ],
+        );
+        assert_eq!(
+            fingerprints[2],
+            DtlsFingerprint::from_certificate_der(b"", FingerprintAlgorithm::Sha256),
+        );
+    }
+
+    #[test]
+    fn ice_candidate_priority() {
+        assert_eq!(compute_ice_priority(126, 65535, 1), 2_130_706_431,);
+
+        let host = IceCandidate::new(
+            "0".to_string(),
+            "10.0.0.1".parse().unwrap(),
+            TransportProtocol::UDP,
+            54400,
+            IceCandidateType::Host,
+        );
+        assert_eq!(host.priority, 2_130_706_431);
+
+        let relay = IceCandidate::new(
+            "1".to_string(),
+            "1.2.3.4".parse().unwrap(),
+            TransportProtocol::UDP,
+            5000,
+            IceCandidateType::Relay,
+        );
+        assert_eq!(relay.priority, 16_776_960 + 255);
+        assert!(relay.priority < host.priority);
+
+        let mut second_transport = IceCandidate::new_with_local_preference(
+            "0".to_string(),
+            "10.0.0.2".parse().unwrap(),
+            TransportProtocol::UDP,
+            54401,
+            IceCandidateType::Host,
+            65534,
+        );
+        assert!(second_transport.priority < host.priority);
+
+        second_transport.set_priority_with_local_preference(65533);
+        assert_eq!(
+            second_transport.priority,
+            compute_ice_priority(126, 65533, DEFAULT_ICE_COMPONENT_ID),
+        );
+    }
+
+    #[test]
+    fn ice_candidate_remote_transport_round_trip() {
+        let tcp_active = "candidate:3 1 tcp 1518280447 192.168.1.1 0 typ host tcptype active";
+        let candidate: IceCandidate = tcp_active.parse().unwrap();
+        assert_eq!(candidate.protocol, TransportProtocol::TCP);
+        assert_eq!(candidate.tcp_type, Some(IceCandidateTcpType::Active));
+        assert_eq!(candidate.to_string(), tcp_active);
+        assert_eq!(
+            candidate.validate_native_transport(),
+            Err(NativeTransportError::UnsupportedTcpType(
+                IceCandidateTcpType::Active
+            )),
+        );
+
+        let quic = "candidate:4 1 quic 1518280447 192.168.1.1 5000 typ host";
+        let candidate: IceCandidate = quic.parse().unwrap();
+        assert_eq!(candidate.protocol, TransportProtocol::QUIC);
+        assert_eq!(candidate.to_string(), quic);
+        assert_eq!(
+            candidate.validate_native_transport(),
+            Err(NativeTransportError::UnsupportedProtocol(
+                TransportProtocol::QUIC
+            )),
+        );
+
+        let native = IceCandidate::new(
+            "0".to_string(),
+            "10.0.0.1".parse().unwrap(),
+            TransportProtocol::UDP,
+            54400,
+            IceCandidateType::Host,
+        );
+        assert_eq!(native.validate_native_transport(), Ok(()));
+    }
+
+    #[test]
+    fn ice_candidate_sdp_attribute() {
+        let candidate = IceCandidate {
+            foundation: "0".to_string(),
+            priority: 2_122_194_687,
+            ip: "10.0.0.1".parse().unwrap(),
+            protocol: TransportProtocol::UDP,
+            port: 54400,
+            r#type: IceCandidateType::Host,
+            tcp_type: None,
+        };
+        let attribute = candidate.to_string();
+        assert_eq!(
+            attribute,
+            "candidate:0 1 udp 2122194687 10.0.0.1 54400 typ host"
+        );
+        assert_eq!(attribute.parse(), Ok(candidate));
+
+        let tcp_candidate = IceCandidate {
+            foundation: "1".to_string(),
+            priority: 1_845_501_695,
+            ip: "10.0.0.1".parse().unwrap(),
+            protocol: TransportProtocol::TCP,
+            port: 9,
+            r#type: IceCandidateType::Host,
+            tcp_type: Some(IceCandidateTcpType::Passive),
+        };
+        let attribute = tcp_candidate.to_string();
+        assert_eq!(
+            attribute,
+            "candidate:1 1 tcp 1845501695 10.0.0.1 9 typ host tcptype passive",
+        );
+        assert_eq!(attribute.parse(), Ok(tcp_candidate));
+
+        // `raddr`/`rport` are accepted (for interop with non-host candidates sent by peers) but
+        // silently dropped, since `IceCandidate` has nowhere to store them.
+        let relay_line =
+            "candidate:2 1 udp 16777215 1.2.3.4 5000 typ relay raddr 10.0.0.1 rport 54400";
+        assert_eq!(
+            relay_line.parse(),
+            Ok(IceCandidate {
+                foundation: "2".to_string(),
+                priority: 16_777_215,
+                ip: "1.2.3.4".parse().unwrap(),
+                protocol: TransportProtocol::UDP,
+                port: 5000,
+                r#type: IceCandidateType::Relay,
+                tcp_type: None,
+            }),
+        );
+
+        assert_eq!(
+            "candidate:0 2 udp 2122194687 10.0.0.1 54400 typ host".parse::<IceCandidate>(),
+            Err(ParseIceCandidateError::InvalidComponentId),
+        );
+        assert_eq!(
+            "candidate:0 1 sctp 2122194687 10.0.0.1 54400 typ host".parse::<IceCandidate>(),
+            Err(ParseIceCandidateError::InvalidTransportProtocol(
+                "sctp".to_string()
+            )),
+        );
+        assert_eq!(
+            "candidate:0 1 udp 2122194687 10.0.0.1 54400 host".parse::<IceCandidate>(),
+            Err(ParseIceCandidateError::MissingTypeKeyword),
+        );
+        assert_eq!(
+            "not-a-candidate:0 1 udp 2122194687 10.0.0.1 54400 typ host".parse::<IceCandidate>(),
+            Err(ParseIceCandidateError::MissingPrefix),
+        );
+        assert_eq!(
+            "candidate:0 1 udp 2122194687 10.0.0.1 54400 typ bogus".parse::<IceCandidate>(),
+            Err(ParseIceCandidateError::InvalidCandidateType(
+                "bogus".to_string()
+            )),
+        );
+    }
+
+    #[test]
+    fn web_rtc_message_try_from_ppid() {
+        assert!(matches!(
+            WebRtcMessage::try_from_ppid(51, Bytes::from_static(b"hello")),
+            Ok(WebRtcMessage::String(string)) if string == "hello",
+        ));
+        assert_eq!(
+            WebRtcMessage::try_from_ppid(51, Bytes::from_static(&[0xff])),
+            Err(WebRtcMessageError::InvalidUtf8),
+        );
+        assert_eq!(
+            WebRtcMessage::try_from_ppid(99, Bytes::new()),
+            Err(WebRtcMessageError::UnknownPpid(99)),
+        );
+    }
+
+    #[test]
+    fn web_rtc_message_reassembler() {
+        let mut reassembler = WebRtcMessageReassembler::new(4);
+
+        assert_eq!(
+            reassembler.accept(0, 54, Bytes::from_static(b"he")),
+            Ok(None),
+        );
+        assert!(matches!(
+            reassembler.accept(0, 51, Bytes::from_static(b"llo")),
+            Ok(Some(WebRtcMessage::String(string))) if string == "hello",
+        ));
+
+        assert_eq!(
+            reassembler.accept(1, 52, Bytes::from_static(b"abcde")),
+            Err(WebRtcMessageError::ReassemblyOverflow {
+                stream_id: 1,
+                limit: 4,
+            }),
+        );
+        // The overflowing stream's buffer was discarded, so a fresh message starts clean.
+        assert!(matches!(
+            reassembler.accept(1, 53, Bytes::from_static(b"ok")),
+            Ok(Some(WebRtcMessage::Binary(_))),
+        ));
+
+        assert_eq!(
+            reassembler.accept(2, 54, Bytes::from_static(b"ab")),
+            Ok(None)
+        );
+        reassembler.reset(2);
+        assert!(matches!(
+            reassembler.accept(2, 51, Bytes::from_static(b"fresh")),
+            Ok(Some(WebRtcMessage::String(string))) if string == "fresh",
+        ));
+    }
 }
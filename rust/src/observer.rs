@@ -0,0 +1,61 @@
+//! Process-wide observer mirroring the JS API's `mediasoup.observer`, so monitoring/metrics code
+//! can attach listeners to every RTP observer created on any router in this process without the
+//! application threading a reference through its own code.
+//!
+//! Coverage is limited to the `RtpObserver` implementations listed in [`NewRtpObserver`];
+//! `AudioLevelObserver` doesn't emit here yet and needs a variant and constructor hookup of its
+//! own before it will.
+
+use crate::active_speaker_observer::ActiveSpeakerObserver;
+use event_listener_primitives::{Bag, HandlerId};
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+
+/// An RTP observer just created on some router in this process, handed to
+/// [`Observer::on_new_rtp_observer`] listeners. One variant per concrete `RtpObserver`
+/// implementation this crate ships; `#[non_exhaustive]` so adding a variant for a future
+/// implementation (e.g. `AudioLevelObserver`) isn't a breaking change.
+#[derive(Clone)]
+#[non_exhaustive]
+pub enum NewRtpObserver {
+    /// An [`ActiveSpeakerObserver`] was created.
+    ActiveSpeaker(ActiveSpeakerObserver),
+}
+
+#[derive(Default)]
+struct Handlers {
+    new_rtp_observer: Bag<Arc<dyn Fn(&NewRtpObserver) + Send + Sync>>,
+}
+
+/// Process-wide observer, see the [module docs](self). Obtain the singleton instance with
+/// [`observer`].
+#[derive(Default)]
+pub struct Observer {
+    handlers: Handlers,
+}
+
+impl Observer {
+    /// Callback invoked whenever an `RtpObserver` variant listed in [`NewRtpObserver`] is created
+    /// on any router in this process. Only covers implementations with a corresponding
+    /// `NewRtpObserver` variant, so a new `RtpObserver` implementation must add one and call
+    /// [`Observer::emit_new_rtp_observer`] from its constructor before creating it fires here.
+    pub fn on_new_rtp_observer<F: Fn(&NewRtpObserver) + Send + Sync + 'static>(
+        &self,
+        callback: F,
+    ) -> HandlerId {
+        self.handlers.new_rtp_observer.add(Arc::new(callback))
+    }
+
+    pub(crate) fn emit_new_rtp_observer(&self, rtp_observer: NewRtpObserver) {
+        self.handlers
+            .new_rtp_observer
+            .call(|callback| callback(&rtp_observer));
+    }
+}
+
+static OBSERVER: Lazy<Observer> = Lazy::new(Observer::default);
+
+/// The process-wide observer, see the [module docs](self).
+pub fn observer() -> &'static Observer {
+    &OBSERVER
+}
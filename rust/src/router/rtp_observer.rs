@@ -62,6 +62,40 @@ pub trait RtpObserver {
     /// Removes the given producer from the RTP observer.
     async fn remove_producer(&self, producer_id: ProducerId) -> Result<(), RequestError>;
 
+    /// The producers currently monitored by this RTP observer, kept consistent as producers are
+    /// added, removed, or implicitly dropped from monitoring when they close.
+    fn producer_ids(&self) -> Vec<ProducerId>;
+
+    /// Provides the RTP observer with several new producers to monitor at once. Fires
+    /// [`RtpObserver::on_add_producer`] for each of `rtp_observer_add_producer_options` in turn.
+    /// The default implementation just calls [`RtpObserver::add_producer`] once per item;
+    /// [`ActiveSpeakerObserver`](crate::active_speaker_observer::ActiveSpeakerObserver) overrides
+    /// this to fold the whole batch into a single pass over its internal state instead.
+    async fn add_producers(
+        &self,
+        rtp_observer_add_producer_options: impl IntoIterator<Item = RtpObserverAddProducerOptions>,
+    ) -> Result<(), RequestError> {
+        for options in rtp_observer_add_producer_options {
+            self.add_producer(options).await?;
+        }
+        Ok(())
+    }
+
+    /// Removes several producers from the RTP observer at once. Fires
+    /// [`RtpObserver::on_remove_producer`] for each of `producer_ids` in turn. The default
+    /// implementation just calls [`RtpObserver::remove_producer`] once per item;
+    /// [`ActiveSpeakerObserver`](crate::active_speaker_observer::ActiveSpeakerObserver) overrides
+    /// this the same way [`RtpObserver::add_producers`] is overridden.
+    async fn remove_producers(
+        &self,
+        producer_ids: impl IntoIterator<Item = ProducerId> + 'async_trait,
+    ) -> Result<(), RequestError> {
+        for producer_id in producer_ids {
+            self.remove_producer(producer_id).await?;
+        }
+        Ok(())
+    }
+
     /// Callback is called when the RTP observer is paused.
     fn on_pause<F: Fn() + Send + Sync + 'static>(&self, callback: F) -> HandlerId;
 
@@ -0,0 +1,226 @@
+//! SDP attribute conversion for [`DtlsParameters`], for gateways and legacy endpoints that speak
+//! raw SDP rather than mediasoup's structured signaling types.
+
+use crate::data_structures::{DtlsFingerprint, DtlsParameters, DtlsRole};
+use thiserror::Error;
+
+/// Error that caused [`DtlsParameters::from_sdp_lines`] to fail.
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum SdpError {
+    /// An `a=fingerprint:` line didn't have the `<algorithm> <value>` shape.
+    #[error("malformed a=fingerprint line: {0}")]
+    MalformedFingerprint(String),
+    /// An `a=fingerprint:` line named a hash algorithm this crate doesn't know how to decode.
+    #[error("unknown fingerprint algorithm: {0}")]
+    UnknownAlgorithm(String),
+    /// An `a=fingerprint:` line's value wasn't a colon-separated hex string of the length its
+    /// algorithm requires.
+    #[error("invalid {algorithm} fingerprint value: {value}")]
+    InvalidFingerprintValue { algorithm: String, value: String },
+    /// An `a=setup:` line named something other than `actpass`/`active`/`passive`.
+    #[error("unknown setup role: {0}")]
+    UnknownSetup(String),
+    /// No `a=fingerprint:` line was present.
+    #[error("no a=fingerprint lines found")]
+    MissingFingerprints,
+    /// No `a=setup:` line was present.
+    #[error("no a=setup line found")]
+    MissingSetup,
+}
+
+fn hex_colon(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn fingerprint_attribute(fingerprint: &DtlsFingerprint) -> String {
+    let (algorithm, value): (&str, &[u8]) = match fingerprint {
+        DtlsFingerprint::Sha1 { value } => ("sha-1", value.as_slice()),
+        DtlsFingerprint::Sha224 { value } => ("sha-224", value.as_slice()),
+        DtlsFingerprint::Sha256 { value } => ("sha-256", value.as_slice()),
+        DtlsFingerprint::Sha384 { value } => ("sha-384", value.as_slice()),
+        DtlsFingerprint::Sha512 { value } => ("sha-512", value.as_slice()),
+    };
+    format!("a=fingerprint:{} {}", algorithm, hex_colon(value))
+}
+
+fn setup_token(role: DtlsRole) -> &'static str {
+    match role {
+        DtlsRole::Auto => "actpass",
+        DtlsRole::Client => "active",
+        DtlsRole::Server => "passive",
+    }
+}
+
+fn parse_fingerprint(algorithm: &str, value: &str) -> Result<DtlsFingerprint, SdpError> {
+    fn parse_hex(algorithm: &str, value: &str, expected_len: usize) -> Result<Vec<u8>, SdpError> {
+        let invalid = || SdpError::InvalidFingerprintValue {
+            algorithm: algorithm.to_string(),
+            value: value.to_string(),
+        };
+
+        let bytes = value
+            .split(':')
+            .map(|byte| u8::from_str_radix(byte, 16).map_err(|_| invalid()))
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        if bytes.len() != expected_len {
+            return Err(invalid());
+        }
+
+        Ok(bytes)
+    }
+
+    match algorithm {
+        "sha-1" => {
+            let mut result = [0u8; 20];
+            result.copy_from_slice(&parse_hex(algorithm, value, 20)?);
+            Ok(DtlsFingerprint::Sha1 { value: result })
+        }
+        "sha-224" => {
+            let mut result = [0u8; 28];
+            result.copy_from_slice(&parse_hex(algorithm, value, 28)?);
+            Ok(DtlsFingerprint::Sha224 { value: result })
+        }
+        "sha-256" => {
+            let mut result = [0u8; 32];
+            result.copy_from_slice(&parse_hex(algorithm, value, 32)?);
+            Ok(DtlsFingerprint::Sha256 { value: result })
+        }
+        "sha-384" => {
+            let mut result = [0u8; 48];
+            result.copy_from_slice(&parse_hex(algorithm, value, 48)?);
+            Ok(DtlsFingerprint::Sha384 { value: result })
+        }
+        "sha-512" => {
+            let mut result = [0u8; 64];
+            result.copy_from_slice(&parse_hex(algorithm, value, 64)?);
+            Ok(DtlsFingerprint::Sha512 { value: result })
+        }
+        other => Err(SdpError::UnknownAlgorithm(other.to_string())),
+    }
+}
+
+impl DtlsParameters {
+    /// Formats these parameters as the SDP attribute lines a media/session section would carry:
+    /// one `a=fingerprint:<algorithm> <VALUE>` line per entry in [`DtlsParameters::fingerprints`]
+    /// (value in the same upper-hex colon form [`DtlsFingerprint`]'s `Serialize` impl produces),
+    /// followed by the `a=setup:` line for [`DtlsParameters::role`].
+    pub fn to_sdp_attributes(&self) -> Vec<String> {
+        let mut attributes: Vec<String> = self
+            .fingerprints
+            .iter()
+            .map(fingerprint_attribute)
+            .collect();
+        attributes.push(format!("a=setup:{}", setup_token(self.role)));
+        attributes
+    }
+
+    /// Parses `DtlsParameters` out of a media/session section's SDP lines, collecting every
+    /// `a=fingerprint:` line (at least one is required) and the section's `a=setup:` role. Lines
+    /// outside this grammar are ignored, since `lines` is expected to be (or be drawn from) a
+    /// full SDP body that also carries unrelated attributes.
+    pub fn from_sdp_lines(lines: &[&str]) -> Result<Self, SdpError> {
+        let mut fingerprints = Vec::new();
+        let mut role = None;
+
+        for line in lines {
+            let line = line.trim();
+
+            if let Some(rest) = line.strip_prefix("a=fingerprint:") {
+                let mut parts = rest.splitn(2, ' ');
+                match (parts.next().filter(|s| !s.is_empty()), parts.next()) {
+                    (Some(algorithm), Some(value)) => {
+                        fingerprints.push(parse_fingerprint(algorithm, value)?);
+                    }
+                    _ => return Err(SdpError::MalformedFingerprint(line.to_string())),
+                }
+            } else if let Some(rest) = line.strip_prefix("a=setup:") {
+                role = Some(match rest {
+                    "actpass" => DtlsRole::Auto,
+                    "active" => DtlsRole::Client,
+                    "passive" => DtlsRole::Server,
+                    other => return Err(SdpError::UnknownSetup(other.to_string())),
+                });
+            }
+        }
+
+        if fingerprints.is_empty() {
+            return Err(SdpError::MissingFingerprints);
+        }
+
+        Ok(DtlsParameters {
+            role: role.ok_or(SdpError::MissingSetup)?,
+            fingerprints,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::FingerprintAlgorithm;
+
+    #[test]
+    fn dtls_parameters_sdp_round_trip() {
+        let parameters = DtlsParameters {
+            role: DtlsRole::Client,
+            fingerprints: vec![DtlsFingerprint::from_certificate_der(
+                b"",
+                FingerprintAlgorithm::Sha256,
+            )],
+        };
+
+        let attributes = parameters.to_sdp_attributes();
+        assert_eq!(
+            attributes,
+            vec![
+                "a=fingerprint:sha-256 E3:B0:C4:42:98:FC:1C:14:9A:FB:F4:C8:99:6F:B9:24:27:AE:41:\
+                 E4:64:9B:93:4C:A4:95:99:1B:78:52:B8:55"
+                    .to_string(),
+                "a=setup:active".to_string(),
+            ],
+        );
+
+        let lines: Vec<&str> = attributes.iter().map(String::as_str).collect();
+        assert_eq!(DtlsParameters::from_sdp_lines(&lines), Ok(parameters));
+    }
+
+    #[test]
+    fn dtls_parameters_from_sdp_lines_ignores_unrelated_lines() {
+        let lines = [
+            "m=application 9 UDP/DTLS/SCTP webrtc-datachannel",
+            "a=mid:0",
+            "a=fingerprint:sha-1 0D:88:5B:EF:B9:86:F9:66:67:75:7A:C1:7A:78:34:E4:88:DC:44:67",
+            "a=setup:passive",
+        ];
+        let parameters = DtlsParameters::from_sdp_lines(&lines).unwrap();
+        assert_eq!(parameters.role, DtlsRole::Server);
+        assert_eq!(parameters.fingerprints.len(), 1);
+    }
+
+    #[test]
+    fn dtls_parameters_from_sdp_lines_rejects_unknown_algorithm() {
+        assert_eq!(
+            DtlsParameters::from_sdp_lines(&["a=fingerprint:sha-200 AA:BB", "a=setup:actpass"]),
+            Err(SdpError::UnknownAlgorithm("sha-200".to_string())),
+        );
+    }
+
+    #[test]
+    fn dtls_parameters_from_sdp_lines_requires_fingerprint_and_setup() {
+        assert_eq!(
+            DtlsParameters::from_sdp_lines(&["a=setup:actpass"]),
+            Err(SdpError::MissingFingerprints),
+        );
+        assert_eq!(
+            DtlsParameters::from_sdp_lines(&[
+                "a=fingerprint:sha-1 0D:88:5B:EF:B9:86:F9:66:67:75:7A:C1:7A:78:34:E4:88:DC:44:67"
+            ]),
+            Err(SdpError::MissingSetup),
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! Trickle-ICE SDP fragments, shaped like WHIP/WHEP's `application/trickle-ice-sdpfrag` content
+//! type: enough SDP syntax to hand a browser mediasoup's ICE-Lite parameters and host candidates
+//! (or a later trickle update) over a PATCH-style signaling channel, without depending on a full
+//! SDP library.
+
+use crate::data_structures::{IceCandidate, IceParameters, ParseIceCandidateError};
+use std::fmt;
+use thiserror::Error;
+
+impl IceParameters {
+    /// Formats this side's `a=ice-ufrag`/`a=ice-pwd` lines for a trickle-ICE SDP fragment. Combine
+    /// with [`SdpFrag`] to additionally carry an `a=mid`/`m=` stub, candidates and
+    /// `a=end-of-candidates`.
+    pub fn to_sdpfrag(&self) -> String {
+        format!(
+            "a=ice-ufrag:{}\r\na=ice-pwd:{}\r\n",
+            self.username_fragment, self.password,
+        )
+    }
+}
+
+/// Error that caused [`SdpFrag::parse`] to fail.
+#[derive(Debug, Error, Eq, PartialEq)]
+pub enum ParseSdpFragError {
+    /// Only one of `a=ice-ufrag`/`a=ice-pwd` was present; they're always sent as a pair.
+    #[error("a=ice-ufrag without a matching a=ice-pwd (or vice versa)")]
+    IncompleteIceParameters,
+    /// An `a=candidate:` line failed to parse, see [`IceCandidate::from_str`].
+    #[error("invalid candidate line: {0}")]
+    InvalidCandidate(#[from] ParseIceCandidateError),
+}
+
+/// A trickle-ICE SDP fragment: an `a=mid`/`m=` stub carrying the session's ICE-Lite parameters,
+/// zero or more `a=candidate:` lines (see [`IceCandidate`]'s [`Display`](fmt::Display)/
+/// [`FromStr`](std::str::FromStr) impls) and the `a=end-of-candidates` marker, see the
+/// [module docs](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SdpFrag {
+    mid: String,
+    ice_parameters: Option<IceParameters>,
+    candidates: Vec<IceCandidate>,
+    end_of_candidates: bool,
+}
+
+impl SdpFrag {
+    /// Starts a new fragment for the media section identified by `mid`, with no ICE parameters or
+    /// candidates yet.
+    pub fn new(mid: impl Into<String>) -> Self {
+        Self {
+            mid: mid.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Attaches the session's ICE-Lite parameters (`a=ice-ufrag`/`a=ice-pwd`) to this fragment.
+    pub fn with_ice_parameters(mut self, ice_parameters: IceParameters) -> Self {
+        self.ice_parameters = Some(ice_parameters);
+        self
+    }
+
+    /// Appends a candidate line (`a=candidate:`) to this fragment, e.g. for a trickled candidate.
+    pub fn push_candidate(&mut self, candidate: IceCandidate) -> &mut Self {
+        self.candidates.push(candidate);
+        self
+    }
+
+    /// Marks this fragment as the last one for its `mid`, emitting `a=end-of-candidates`.
+    pub fn with_end_of_candidates(mut self) -> Self {
+        self.end_of_candidates = true;
+        self
+    }
+
+    /// The `mid` this fragment's lines apply to.
+    pub fn mid(&self) -> &str {
+        &self.mid
+    }
+
+    /// The ICE parameters carried by this fragment, if any.
+    pub fn ice_parameters(&self) -> Option<&IceParameters> {
+        self.ice_parameters.as_ref()
+    }
+
+    /// The candidates carried by this fragment.
+    pub fn candidates(&self) -> &[IceCandidate] {
+        &self.candidates
+    }
+
+    /// Whether this fragment carries `a=end-of-candidates`.
+    pub fn end_of_candidates(&self) -> bool {
+        self.end_of_candidates
+    }
+
+    /// Parses a trickle-ICE SDP fragment, returning its ICE parameters (if present, requiring
+    /// both `a=ice-ufrag` and `a=ice-pwd`), its candidates in order, and whether
+    /// `a=end-of-candidates` was seen. Lines outside this small ICE-only grammar (an `m=` stub,
+    /// `a=mid`, or anything else a full SDP body might carry around the fragment) are ignored
+    /// rather than rejected, since this isn't a general-purpose SDP parser.
+    pub fn parse(
+        input: &str,
+    ) -> Result<(Option<IceParameters>, Vec<IceCandidate>, bool), ParseSdpFragError> {
+        let mut username_fragment = None;
+        let mut password = None;
+        let mut candidates = Vec::new();
+        let mut end_of_candidates = false;
+
+        for line in input.lines() {
+            let line = line.trim();
+
+            if let Some(value) = line.strip_prefix("a=ice-ufrag:") {
+                username_fragment = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("a=ice-pwd:") {
+                password = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("a=candidate:") {
+                candidates.push(format!("candidate:{}", value).parse()?);
+            } else if line == "a=end-of-candidates" {
+                end_of_candidates = true;
+            }
+        }
+
+        let ice_parameters = match (username_fragment, password) {
+            (Some(username_fragment), Some(password)) => Some(IceParameters {
+                username_fragment,
+                password,
+                // Not carried by a trickle-ICE fragment; it's negotiated at the session level.
+                ice_lite: None,
+            }),
+            (None, None) => None,
+            _ => return Err(ParseSdpFragError::IncompleteIceParameters),
+        };
+
+        Ok((ice_parameters, candidates, end_of_candidates))
+    }
+}
+
+impl fmt::Display for SdpFrag {
+    /// Formats this fragment as SDP lines terminated with `\r\n`, per RFC 4566.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ice_parameters) = &self.ice_parameters {
+            write!(f, "{}", ice_parameters.to_sdpfrag())?;
+        }
+
+        writeln!(f, "m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r")?;
+        writeln!(f, "a=mid:{}\r", self.mid)?;
+
+        for candidate in &self.candidates {
+            writeln!(f, "a={}\r", candidate)?;
+        }
+
+        if self.end_of_candidates {
+            writeln!(f, "a=end-of-candidates\r")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_structures::{IceCandidateType, TransportProtocol};
+
+    #[test]
+    fn sdp_frag_round_trip() {
+        let ice_parameters = IceParameters {
+            username_fragment: "ufrag".to_string(),
+            password: "password".to_string(),
+            ice_lite: Some(true),
+        };
+        let candidate = IceCandidate::new(
+            "0".to_string(),
+            "10.0.0.1".parse().unwrap(),
+            TransportProtocol::UDP,
+            54400,
+            IceCandidateType::Host,
+        );
+
+        let mut frag = SdpFrag::new("0").with_ice_parameters(ice_parameters.clone());
+        frag.push_candidate(candidate.clone());
+        let frag = frag.with_end_of_candidates();
+
+        let text = frag.to_string();
+        assert!(text.contains("a=ice-ufrag:ufrag\r\n"));
+        assert!(text.contains("a=ice-pwd:password\r\n"));
+        assert!(text.contains(&format!("a={}\r\n", candidate)));
+        assert!(text.contains("a=end-of-candidates\r\n"));
+
+        let (parsed_ice_parameters, parsed_candidates, end_of_candidates) =
+            SdpFrag::parse(&text).unwrap();
+        assert_eq!(
+            parsed_ice_parameters,
+            Some(IceParameters {
+                ice_lite: None,
+                ..ice_parameters
+            }),
+        );
+        assert_eq!(parsed_candidates, vec![candidate]);
+        assert!(end_of_candidates);
+    }
+
+    #[test]
+    fn sdp_frag_without_ice_parameters_or_end_marker() {
+        let (ice_parameters, candidates, end_of_candidates) =
+            SdpFrag::parse("m=application 9 UDP/DTLS/SCTP webrtc-datachannel\r\na=mid:0\r\n")
+                .unwrap();
+        assert_eq!(ice_parameters, None);
+        assert!(candidates.is_empty());
+        assert!(!end_of_candidates);
+    }
+
+    #[test]
+    fn sdp_frag_rejects_incomplete_ice_parameters() {
+        assert_eq!(
+            SdpFrag::parse("a=ice-ufrag:ufrag\r\n"),
+            Err(ParseSdpFragError::IncompleteIceParameters),
+        );
+    }
+
+    #[test]
+    fn sdp_frag_rejects_invalid_candidate_line() {
+        assert!(matches!(
+            SdpFrag::parse("a=candidate:not a real candidate\r\n"),
+            Err(ParseSdpFragError::InvalidCandidate(_)),
+        ));
+    }
+}
@@ -0,0 +1,55 @@
+use serde_json::Value;
+use thiserror::Error;
+
+/// Error produced while decoding a message off the wire.
+#[derive(Debug, Error)]
+pub(crate) enum CodecError {
+    #[error("Failed to decode message: {0}")]
+    Decode(String),
+}
+
+/// Wire codec used to (de)serialize the body of messages exchanged with the worker over the
+/// payload channel. The netstring framing (`len:bytes,`) is independent of this trait; only the
+/// body representation changes between implementations.
+pub(crate) trait ChannelCodec: Send + Sync {
+    /// Encode a JSON value into its wire representation.
+    fn encode(&self, value: &Value) -> Vec<u8>;
+
+    /// Decode a wire representation back into a JSON value.
+    fn decode(&self, bytes: &[u8]) -> Result<Value, CodecError>;
+}
+
+/// Default codec, matching the JSON body mediasoup workers have always spoken.
+#[derive(Default)]
+pub(crate) struct JsonCodec;
+
+impl ChannelCodec for JsonCodec {
+    fn encode(&self, value: &Value) -> Vec<u8> {
+        serde_json::to_vec(value).unwrap()
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, CodecError> {
+        serde_json::from_slice(bytes).map_err(|error| CodecError::Decode(error.to_string()))
+    }
+}
+
+/// Compact binary codec for high-fan-out deployments where JSON (de)serialization becomes a
+/// measurable cost (RTP stats polling, consumer dumps, etc). Passed to
+/// [`super::payload_channel::PayloadChannel::new_with_codec`], which only switches the channel
+/// onto it once the worker has acknowledged the handshake that codec selection is gated behind;
+/// until then (and if the worker never acknowledges) the channel keeps speaking
+/// [`JsonCodec`].
+#[cfg(feature = "bincode-codec")]
+#[derive(Default)]
+pub(crate) struct BincodeCodec;
+
+#[cfg(feature = "bincode-codec")]
+impl ChannelCodec for BincodeCodec {
+    fn encode(&self, value: &Value) -> Vec<u8> {
+        bincode::serialize(value).expect("Failed to serialize value with bincode")
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value, CodecError> {
+        bincode::deserialize(bytes).map_err(|error| CodecError::Decode(error.to_string()))
+    }
+}
@@ -0,0 +1,189 @@
+//! A small, self-describing binary record format for the control channel's `{`-tagged frames,
+//! replacing the old scheme of handing callers a raw (and `unsafe`-assumed-UTF8) JSON string to
+//! parse a second time.
+//!
+//! A record is a one-byte kind tag followed by a sequence of fields. Each field is itself tagged
+//! and length-prefixed (`tag: u8, len: u32 LE, bytes: [u8; len]`), so a reader skips fields it
+//! doesn't recognize instead of failing outright when the host and worker binaries drift apart.
+
+use thiserror::Error;
+
+const KIND_REQUEST: u8 = 0;
+const KIND_RESPONSE: u8 = 1;
+const KIND_NOTIFICATION: u8 = 2;
+
+const FIELD_ID: u8 = 0;
+const FIELD_METHOD: u8 = 1;
+const FIELD_ACCEPTED: u8 = 2;
+const FIELD_DATA: u8 = 3;
+const FIELD_TARGET_ID: u8 = 4;
+const FIELD_EVENT: u8 = 5;
+
+#[derive(Debug, Error)]
+pub(crate) enum RecordDecodeError {
+    #[error("record is empty")]
+    Empty,
+    #[error("unknown record kind {0}")]
+    UnknownKind(u8),
+    #[error("truncated field header")]
+    TruncatedFieldHeader,
+    #[error("truncated field body, expected {expected} bytes, got {actual}")]
+    TruncatedFieldBody { expected: usize, actual: usize },
+    #[error("missing required field {0}")]
+    MissingField(&'static str),
+    #[error("field {0} is not valid UTF-8")]
+    InvalidUtf8(&'static str),
+}
+
+/// A control-channel record decoded from its tagged binary wire form, see [`decode`].
+#[derive(Debug, Clone)]
+pub(crate) enum ChannelRecord {
+    /// A request sent from the host to the worker (only ever produced on the write side, via
+    /// [`encode_request`], but included here for a symmetric, versionable schema).
+    Request {
+        id: u32,
+        method: String,
+        data: Vec<u8>,
+    },
+    /// A response to a request previously sent by this side.
+    Response {
+        id: u32,
+        accepted: bool,
+        data: Vec<u8>,
+    },
+    /// An event not tied to any particular request.
+    Notification {
+        target_id: String,
+        event: String,
+        data: Vec<u8>,
+    },
+}
+
+struct RawField<'a> {
+    tag: u8,
+    bytes: &'a [u8],
+}
+
+fn iter_fields(bytes: &[u8]) -> Result<Vec<RawField<'_>>, RecordDecodeError> {
+    let mut fields = Vec::new();
+    let mut cursor = 0;
+
+    while cursor < bytes.len() {
+        if bytes.len() - cursor < 5 {
+            return Err(RecordDecodeError::TruncatedFieldHeader);
+        }
+        let tag = bytes[cursor];
+        let len = u32::from_le_bytes([
+            bytes[cursor + 1],
+            bytes[cursor + 2],
+            bytes[cursor + 3],
+            bytes[cursor + 4],
+        ]) as usize;
+        cursor += 5;
+
+        if bytes.len() - cursor < len {
+            return Err(RecordDecodeError::TruncatedFieldBody {
+                expected: len,
+                actual: bytes.len() - cursor,
+            });
+        }
+        fields.push(RawField {
+            tag,
+            bytes: &bytes[cursor..cursor + len],
+        });
+        cursor += len;
+    }
+
+    Ok(fields)
+}
+
+fn field<'a>(
+    fields: &'a [RawField<'a>],
+    tag: u8,
+    name: &'static str,
+) -> Result<&'a [u8], RecordDecodeError> {
+    fields
+        .iter()
+        .find(|field| field.tag == tag)
+        .map(|field| field.bytes)
+        .ok_or(RecordDecodeError::MissingField(name))
+}
+
+fn field_u32(fields: &[RawField], tag: u8, name: &'static str) -> Result<u32, RecordDecodeError> {
+    let bytes = field(fields, tag, name)?;
+    let bytes: [u8; 4] = bytes
+        .try_into()
+        .map_err(|_| RecordDecodeError::MissingField(name))?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn field_string(
+    fields: &[RawField],
+    tag: u8,
+    name: &'static str,
+) -> Result<String, RecordDecodeError> {
+    String::from_utf8(field(fields, tag, name)?.to_vec())
+        .map_err(|_| RecordDecodeError::InvalidUtf8(name))
+}
+
+fn field_bool(fields: &[RawField], tag: u8, name: &'static str) -> Result<bool, RecordDecodeError> {
+    Ok(field(fields, tag, name)?.first().copied().unwrap_or(0) != 0)
+}
+
+fn field_data(fields: &[RawField], tag: u8) -> Vec<u8> {
+    fields
+        .iter()
+        .find(|field| field.tag == tag)
+        .map(|field| field.bytes.to_vec())
+        .unwrap_or_default()
+}
+
+/// Decodes a tagged binary record body: everything the control channel received after its `{`
+/// command byte.
+pub(crate) fn decode(bytes: &[u8]) -> Result<ChannelRecord, RecordDecodeError> {
+    let kind = *bytes.first().ok_or(RecordDecodeError::Empty)?;
+    let fields = iter_fields(&bytes[1..])?;
+
+    match kind {
+        KIND_REQUEST => Ok(ChannelRecord::Request {
+            id: field_u32(&fields, FIELD_ID, "id")?,
+            method: field_string(&fields, FIELD_METHOD, "method")?,
+            data: field_data(&fields, FIELD_DATA),
+        }),
+        KIND_RESPONSE => Ok(ChannelRecord::Response {
+            id: field_u32(&fields, FIELD_ID, "id")?,
+            accepted: field_bool(&fields, FIELD_ACCEPTED, "accepted")?,
+            data: field_data(&fields, FIELD_DATA),
+        }),
+        KIND_NOTIFICATION => Ok(ChannelRecord::Notification {
+            target_id: field_string(&fields, FIELD_TARGET_ID, "target_id")?,
+            event: field_string(&fields, FIELD_EVENT, "event")?,
+            data: field_data(&fields, FIELD_DATA),
+        }),
+        _ => Err(RecordDecodeError::UnknownKind(kind)),
+    }
+}
+
+fn push_field(buf: &mut Vec<u8>, tag: u8, bytes: &[u8]) {
+    buf.push(tag);
+    buf.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    buf.extend_from_slice(bytes);
+}
+
+/// Encodes a request record, returning the full control-channel frame body including the
+/// leading `{` command byte.
+pub(crate) fn encode_request(id: u32, method: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = vec![b'{', KIND_REQUEST];
+    push_field(&mut body, FIELD_ID, &id.to_le_bytes());
+    push_field(&mut body, FIELD_METHOD, method.as_bytes());
+    push_field(&mut body, FIELD_DATA, data);
+    body
+}
+
+/// A request queued to be sent to the worker over the control channel, see [`encode_request`].
+#[derive(Debug, Clone)]
+pub(crate) struct ChannelRequest {
+    pub(crate) id: u32,
+    pub(crate) method: String,
+    pub(crate) data: Vec<u8>,
+}
@@ -1,23 +1,162 @@
 // Contents of this module is inspired by https://github.com/Srinivasa314/alcro/tree/master/src/chrome
+pub(crate) use crate::worker::channel_record::ChannelRequest;
+use crate::worker::channel_record::{self, ChannelRecord};
+use crate::worker::payload_compression::PayloadCompressionConfig;
 use async_channel::{Receiver, Sender};
 use async_executor::Executor;
 use async_fs::File as AsyncFile;
 use async_process::unix::CommandExt;
 use async_process::{Child, Command};
 use futures_lite::io::BufReader;
-use futures_lite::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
-use nix::unistd;
+use futures_lite::{future, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+use log::*;
+use nix::sys::signal::{self, Signal};
+use nix::unistd::{self, Pid};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fs::File as StdFile;
 use std::io;
 use std::os::unix::io::{FromRawFd, RawFd};
+use std::process::ExitStatus;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
 
-// netstring length for a 4194304 bytes payload.
-const NS_PAYLOAD_MAX_LEN: usize = 4194304;
+// Maximum size of a single wire chunk's payload, not counting the frame header. Large messages
+// are split across multiple chunks rather than growing this, so the reader never has to
+// preallocate more than this much memory per in-flight chunk.
+const STREAM_CHUNK_MAX_LEN: usize = 16 * 1024;
+// Frame header: command byte + frame kind byte + 4-byte little-endian stream id.
+const FRAME_HEADER_LEN: usize = 6;
+// Command byte marking a frame whose reassembled body is `compressor.compress(original_command ++
+// original_body)`; keeps workers that don't understand compression on the plain path when they
+// never see this byte.
+const COMPRESSED_COMMAND: u8 = b'Z';
+// Sanity cap on a fully reassembled message, protecting against a peer that never sends a final
+// chunk.
+const MESSAGE_MAX_LEN: usize = 4194304;
+// How often the reader checks for stalled reassembly streams, independent of whether new chunks
+// are arriving for *other* streams.
+const STREAM_SWEEP_INTERVAL: Duration = Duration::from_secs(5);
+// How long a stream may go without a new chunk before it's dropped as abandoned.
+const STREAM_REASSEMBLY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Capacity of the bounded internal queues a [`Channel`] uses to hand `ChannelRequest`s to its
+/// writer task and `ChannelMessage`s back to callers. The control channel and payload channel are
+/// each configured independently, so one can be given more headroom than the other under load.
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelCapacities {
+    pub outgoing: usize,
+    pub incoming: usize,
+}
+
+impl Default for ChannelCapacities {
+    fn default() -> Self {
+        Self {
+            outgoing: 1,
+            incoming: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ChannelMetricsInner {
+    messages_sent: AtomicU64,
+    bytes_sent: AtomicU64,
+    messages_received: AtomicU64,
+    bytes_received: AtomicU64,
+    backpressured_sends: AtomicU64,
+    dropped_sends: AtomicU64,
+}
+
+/// Lock-free counters tracking throughput and backpressure on a [`Channel`], so its queue
+/// capacities can be chosen from observed behavior instead of guesswork.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelMetrics {
+    inner: Arc<ChannelMetricsInner>,
+}
+
+impl ChannelMetrics {
+    fn record_sent(&self, bytes: usize) {
+        self.inner.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.inner
+            .bytes_sent
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    fn record_received(&self, bytes: usize) {
+        self.inner.messages_received.fetch_add(1, Ordering::Relaxed);
+        self.inner
+            .bytes_received
+            .fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    fn record_backpressured(&self) {
+        self.inner
+            .backpressured_sends
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_dropped(&self) {
+        self.inner.dropped_sends.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the current counters, combined with the live queue depths of `outgoing` and
+    /// `incoming` at the moment of the call.
+    fn snapshot(
+        &self,
+        outgoing_queue_depth: usize,
+        incoming_queue_depth: usize,
+    ) -> ChannelMetricsSnapshot {
+        ChannelMetricsSnapshot {
+            messages_sent: self.inner.messages_sent.load(Ordering::Relaxed),
+            bytes_sent: self.inner.bytes_sent.load(Ordering::Relaxed),
+            messages_received: self.inner.messages_received.load(Ordering::Relaxed),
+            bytes_received: self.inner.bytes_received.load(Ordering::Relaxed),
+            backpressured_sends: self.inner.backpressured_sends.load(Ordering::Relaxed),
+            dropped_sends: self.inner.dropped_sends.load(Ordering::Relaxed),
+            outgoing_queue_depth,
+            incoming_queue_depth,
+        }
+    }
+}
+
+/// Point-in-time view of a [`Channel`]'s throughput and queue saturation, see [`Channel::metrics`].
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelMetricsSnapshot {
+    /// Requests the writer task has written to the wire.
+    pub messages_sent: u64,
+    /// Bytes of request payload written to the wire (post-compression, pre-chunking).
+    pub bytes_sent: u64,
+    /// Messages handed to callers off the reader task.
+    pub messages_received: u64,
+    /// Bytes of message payload handed to callers.
+    pub bytes_received: u64,
+    /// Hand-offs to the caller's `Receiver` that had to wait for queue space.
+    pub backpressured_sends: u64,
+    /// Messages that couldn't be delivered because the caller had dropped its `Receiver`.
+    pub dropped_sends: u64,
+    /// Requests queued for the writer task right now.
+    pub outgoing_queue_depth: usize,
+    /// Messages queued for the caller to receive right now.
+    pub incoming_queue_depth: usize,
+}
 
 #[derive(Debug)]
 pub enum ChannelMessage {
-    /// JSON message
-    Json(String),
+    /// A response to a request previously sent over this channel.
+    Response {
+        id: u32,
+        accepted: bool,
+        data: Vec<u8>,
+    },
+    /// An event not tied to any particular request.
+    Notification {
+        target_id: String,
+        event: String,
+        data: Vec<u8>,
+    },
     /// Debug log
     Debug(String),
     /// Warn log
@@ -28,53 +167,311 @@ pub enum ChannelMessage {
     Dump(String),
     /// Unknown
     Unknown { command: u8, data: Vec<u8> },
+    /// The pipe backing this channel reached EOF or produced a frame that couldn't be parsed.
+    /// Sent once, immediately before the channel's receiver stream ends, so callers can tell a
+    /// dead worker apart from a `Receiver` that simply has nothing new to report yet.
+    ChannelClosed,
+}
+
+/// Internal error produced by the reader/writer tasks in [`create_channel_pair`]. Both tasks
+/// propagate this instead of panicking on a malformed frame, so a corrupt message from a
+/// misbehaving peer ends the channel cleanly rather than taking down the executor.
+#[derive(Debug, Error)]
+enum ChannelError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("malformed netstring length prefix {0:?}")]
+    MalformedLength(Vec<u8>),
+    #[error("frame length {length} exceeds maximum chunk size {max}")]
+    FrameTooLarge { length: usize, max: usize },
+    #[error("frame length {length} is too short to hold the {min}-byte frame header")]
+    FrameTooShort { length: usize, min: usize },
 }
 
 fn deserialize_message(command: u8, data: Vec<u8>) -> ChannelMessage {
     match command {
-        // JSON message
-        b'{' => ChannelMessage::Json(unsafe { String::from_utf8_unchecked(data) }),
+        // Tagged binary record, see `channel_record`
+        b'{' => match channel_record::decode(&data) {
+            Ok(ChannelRecord::Response { id, accepted, data }) => {
+                ChannelMessage::Response { id, accepted, data }
+            }
+            Ok(ChannelRecord::Notification {
+                target_id,
+                event,
+                data,
+            }) => ChannelMessage::Notification {
+                target_id,
+                event,
+                data,
+            },
+            Ok(ChannelRecord::Request { .. }) => {
+                warn!("received a request record on the read side, ignoring");
+                ChannelMessage::Unknown { command, data }
+            }
+            Err(error) => {
+                warn!("failed to decode control channel record: {}", error);
+                ChannelMessage::Unknown { command, data }
+            }
+        },
         // Debug log
-        b'D' => ChannelMessage::Debug(unsafe { String::from_utf8_unchecked(data) }),
+        b'D' => ChannelMessage::Debug(String::from_utf8_lossy(&data).into_owned()),
         // Warn log
-        b'W' => ChannelMessage::Warn(unsafe { String::from_utf8_unchecked(data) }),
+        b'W' => ChannelMessage::Warn(String::from_utf8_lossy(&data).into_owned()),
         // Error log
-        b'E' => ChannelMessage::Error(unsafe { String::from_utf8_unchecked(data) }),
+        b'E' => ChannelMessage::Error(String::from_utf8_lossy(&data).into_owned()),
         // Dump log
-        b'X' => ChannelMessage::Dump(unsafe { String::from_utf8_unchecked(data) }),
+        b'X' => ChannelMessage::Dump(String::from_utf8_lossy(&data).into_owned()),
         // Unknown
         _ => ChannelMessage::Unknown { command, data },
     }
 }
 
+/// Position of a chunk within a reassembled message, carried as the second byte of the frame
+/// header right after the command byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FrameKind {
+    /// More chunks for this stream id follow.
+    Continuation,
+    /// Last chunk for this stream id; the reassembled message is complete.
+    Final,
+}
+
+impl FrameKind {
+    fn from_byte(byte: u8) -> Option<Self> {
+        match byte {
+            b'C' => Some(Self::Continuation),
+            b'F' => Some(Self::Final),
+            _ => None,
+        }
+    }
+
+    fn as_byte(self) -> u8 {
+        match self {
+            Self::Continuation => b'C',
+            Self::Final => b'F',
+        }
+    }
+}
+
+/// Chunks received so far for a stream id that hasn't seen its final chunk yet.
+struct PartialMessage {
+    buffer: Vec<u8>,
+    last_chunk_at: Instant,
+}
+
+/// Drops reassembly state for streams that haven't received a chunk in `STREAM_REASSEMBLY_TIMEOUT`,
+/// which otherwise would grow unbounded if the peer died mid-send.
+fn sweep_stale_reassembly(reassembly: &mut HashMap<u32, PartialMessage>) {
+    let now = Instant::now();
+
+    reassembly.retain(|stream_id, partial| {
+        let alive = now.duration_since(partial.last_chunk_at) < STREAM_REASSEMBLY_TIMEOUT;
+        if !alive {
+            warn!(
+                "dropping stream [id:{}] that stalled for longer than {:?}",
+                stream_id, STREAM_REASSEMBLY_TIMEOUT,
+            );
+        }
+        alive
+    });
+}
+
+enum ReadOutcome {
+    Frame(usize),
+    SweepTick,
+}
+
+/// Decompresses a reassembled `COMPRESSED_COMMAND` body back into the original command byte and
+/// payload. Returns `None` (after logging) if there's no decompressor configured or decompression
+/// fails, in which case the frame is dropped rather than misinterpreted.
+fn decompress_frame(compression: &PayloadCompressionConfig, data: &[u8]) -> Option<(u8, Vec<u8>)> {
+    let compressor = match &compression.compressor {
+        Some(compressor) => compressor,
+        None => {
+            warn!("received compressed frame but no decompressor is configured");
+            return None;
+        }
+    };
+
+    let decompressed = match compressor.decompress(data) {
+        Ok(decompressed) => decompressed,
+        Err(error) => {
+            warn!("failed to decompress frame: {}", error);
+            return None;
+        }
+    };
+
+    if decompressed.is_empty() {
+        warn!("received empty decompressed frame");
+        return None;
+    }
+
+    Some((decompressed[0], decompressed[1..].to_vec()))
+}
+
 fn create_channel_pair(
     executor: &Executor,
     reader: AsyncFile,
     mut writer: AsyncFile,
-) -> (Sender<Vec<u8>>, Receiver<ChannelMessage>) {
+    compression: PayloadCompressionConfig,
+    capacities: ChannelCapacities,
+) -> Channel {
+    let metrics = ChannelMetrics::default();
+
     let receiver = {
-        let (sender, receiver) = async_channel::bounded(1);
+        let (sender, receiver) = async_channel::bounded(capacities.incoming);
+        let compression = compression.clone();
+        let metrics = metrics.clone();
 
         executor
             .spawn(async move {
-                let mut bytes = vec![0u8; NS_PAYLOAD_MAX_LEN];
+                let mut len_bytes = Vec::new();
+                let mut bytes = vec![0u8; FRAME_HEADER_LEN + STREAM_CHUNK_MAX_LEN];
                 let mut reader = BufReader::new(reader);
+                let mut reassembly = HashMap::<u32, PartialMessage>::new();
+                let mut next_sweep = Instant::now() + STREAM_SWEEP_INTERVAL;
+
+                let result = loop {
+                    // Checked on every iteration, not only when the timer below wins the race, so
+                    // steady traffic on other streams can't postpone the sweep indefinitely.
+                    if Instant::now() >= next_sweep {
+                        sweep_stale_reassembly(&mut reassembly);
+                        next_sweep = Instant::now() + STREAM_SWEEP_INTERVAL;
+                    }
+
+                    let outcome = match future::or(
+                        async { reader.read_until(b':', &mut len_bytes).await.map(ReadOutcome::Frame) },
+                        async {
+                            async_io::Timer::at(next_sweep).await;
+                            io::Result::Ok(ReadOutcome::SweepTick)
+                        },
+                    )
+                    .await
+                    {
+                        Ok(outcome) => outcome,
+                        Err(error) => break Err(ChannelError::from(error)),
+                    };
 
-                loop {
-                    let read_bytes = reader.read_until(b':', &mut bytes).await?;
-                    bytes.pop();
-                    let length = String::from_utf8_lossy(&bytes[..read_bytes])
+                    let read_bytes = match outcome {
+                        ReadOutcome::Frame(read_bytes) => read_bytes,
+                        ReadOutcome::SweepTick => {
+                            // The sweep itself runs at the top of the loop. The in-progress
+                            // `read_until` is dropped along with its future on this branch of the
+                            // `or()`, but any length prefix bytes it already consumed were
+                            // appended to `len_bytes`, which is kept, so the next iteration's
+                            // `read_until` continues the same prefix.
+                            continue;
+                        }
+                    };
+                    if read_bytes == 0 {
+                        // EOF: the worker closed its end, most likely because it died.
+                        break Ok(());
+                    }
+                    let length = match String::from_utf8_lossy(&len_bytes[..(len_bytes.len() - 1)])
                         .parse::<usize>()
-                        .unwrap();
+                    {
+                        Ok(length) => length,
+                        Err(_) => break Err(ChannelError::MalformedLength(len_bytes)),
+                    };
+                    len_bytes.clear();
+
+                    // The wire-supplied length must fit the preallocated frame buffer (a frame's
+                    // chunk payload is capped at `STREAM_CHUNK_MAX_LEN` on the writer side), or
+                    // slicing `bytes` below would panic instead of reporting a protocol error.
+                    if length + 1 > bytes.len() {
+                        break Err(ChannelError::FrameTooLarge {
+                            length,
+                            max: bytes.len() - 1,
+                        });
+                    }
+                    // It must also be long enough to hold the frame header, or slicing out the
+                    // chunk body below would panic instead of reporting a protocol error.
+                    if length < FRAME_HEADER_LEN {
+                        break Err(ChannelError::FrameTooShort {
+                            length,
+                            min: FRAME_HEADER_LEN,
+                        });
+                    }
+
                     // +1 because of netstring's `,` at the very end
-                    reader.read_exact(&mut bytes[..(length + 1)]).await?;
-                    // TODO: Parse messages here and send parsed messages over the channel
-                    let message = deserialize_message(bytes[0], Vec::from(&bytes[1..length]));
-                    println!("Received");
-                    let _ = sender.send(message);
+                    if let Err(error) = reader.read_exact(&mut bytes[..(length + 1)]).await {
+                        break Err(ChannelError::from(error));
+                    }
+
+                    let command = bytes[0];
+                    let frame_kind = FrameKind::from_byte(bytes[1]);
+                    let stream_id = u32::from_le_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]);
+                    let chunk = &bytes[FRAME_HEADER_LEN..length];
+
+                    match frame_kind {
+                        Some(FrameKind::Continuation) => {
+                            let partial = reassembly.entry(stream_id).or_insert_with(|| {
+                                PartialMessage {
+                                    buffer: Vec::new(),
+                                    last_chunk_at: Instant::now(),
+                                }
+                            });
+                            partial.buffer.extend_from_slice(chunk);
+                            partial.last_chunk_at = Instant::now();
+
+                            if partial.buffer.len() > MESSAGE_MAX_LEN {
+                                warn!(
+                                    "dropping stream [id:{}], reassembled message exceeded {} bytes",
+                                    stream_id, MESSAGE_MAX_LEN,
+                                );
+                                reassembly.remove(&stream_id);
+                            }
+                        }
+                        Some(FrameKind::Final) => {
+                            let data = match reassembly.remove(&stream_id) {
+                                Some(mut partial) => {
+                                    partial.buffer.extend_from_slice(chunk);
+                                    partial.buffer
+                                }
+                                None => Vec::from(chunk),
+                            };
+
+                            let decoded = if command == COMPRESSED_COMMAND {
+                                decompress_frame(&compression, &data)
+                            } else {
+                                Some((command, data))
+                            };
+
+                            if let Some((command, data)) = decoded {
+                                let data_len = data.len();
+                                let message = deserialize_message(command, data);
+
+                                match sender.try_send(message) {
+                                    Ok(()) => metrics.record_received(data_len),
+                                    Err(async_channel::TrySendError::Full(message)) => {
+                                        metrics.record_backpressured();
+                                        if sender.send(message).await.is_ok() {
+                                            metrics.record_received(data_len);
+                                        } else {
+                                            metrics.record_dropped();
+                                        }
+                                    }
+                                    Err(async_channel::TrySendError::Closed(_)) => {
+                                        metrics.record_dropped();
+                                    }
+                                }
+                            }
+                        }
+                        None => {
+                            warn!(
+                                "received frame with unknown kind {:?} [stream id:{}], ignoring",
+                                bytes[1], stream_id,
+                            );
+                        }
+                    }
+                };
+
+                if let Err(error) = &result {
+                    error!("channel reader task ending: {}", error);
                 }
+                let _ = sender.send(ChannelMessage::ChannelClosed).await;
 
-                io::Result::Ok(())
+                result
             })
             .detach();
 
@@ -82,37 +479,172 @@ fn create_channel_pair(
     };
 
     let sender = {
-        let (sender, receiver) = async_channel::bounded::<Vec<u8>>(1);
+        let (sender, receiver) = async_channel::bounded::<ChannelRequest>(capacities.outgoing);
+        let metrics = metrics.clone();
 
         executor
             .spawn(async move {
-                let mut bytes = Vec::with_capacity(NS_PAYLOAD_MAX_LEN);
-                // TODO: Stringify messages here and received non-stringified messages over the
-                //  channel
-                while let Ok(message) = receiver.recv().await {
-                    bytes.clear();
-                    bytes.extend_from_slice(message.len().to_string().as_bytes());
-                    bytes.push(b':');
-                    bytes.extend_from_slice(&message);
-                    bytes.push(b',');
-
-                    writer.write_all(&bytes).await?;
+                let mut bytes = Vec::with_capacity(FRAME_HEADER_LEN + STREAM_CHUNK_MAX_LEN);
+                let mut next_stream_id: u32 = 0;
+
+                let result = 'writer: loop {
+                    let request = match receiver.recv().await {
+                        Ok(request) => request,
+                        Err(_) => break 'writer Ok(()),
+                    };
+                    let message =
+                        channel_record::encode_request(request.id, &request.method, &request.data);
+                    let command = message[0];
+                    let payload = &message[1..];
+
+                    // Compress the body before chunking it, so the chunk/frame-count the other
+                    // side reassembles already reflects the smaller, compressed size.
+                    let (command, payload): (u8, Cow<[u8]>) = match &compression.compressor {
+                        Some(compressor) if payload.len() > compression.threshold => {
+                            let mut original = Vec::with_capacity(1 + payload.len());
+                            original.push(command);
+                            original.extend_from_slice(payload);
+                            (COMPRESSED_COMMAND, Cow::Owned(compressor.compress(&original)))
+                        }
+                        _ => (command, Cow::Borrowed(payload)),
+                    };
+                    let payload: &[u8] = &payload;
+
+                    let stream_id = next_stream_id;
+                    next_stream_id = next_stream_id.wrapping_add(1);
+
+                    let mut chunks = payload.chunks(STREAM_CHUNK_MAX_LEN).peekable();
+                    // `payload.chunks()` on an empty slice yields nothing, but an empty message
+                    // still needs its single final frame sent.
+                    if chunks.peek().is_none() {
+                        let frame_kind = FrameKind::Final;
+
+                        bytes.clear();
+                        bytes.extend_from_slice(FRAME_HEADER_LEN.to_string().as_bytes());
+                        bytes.push(b':');
+                        bytes.push(command);
+                        bytes.push(frame_kind.as_byte());
+                        bytes.extend_from_slice(&stream_id.to_le_bytes());
+                        bytes.push(b',');
+
+                        if let Err(error) = writer.write_all(&bytes).await {
+                            break 'writer Err(error);
+                        }
+                        metrics.record_sent(payload.len());
+                        continue 'writer;
+                    }
+
+                    while let Some(chunk) = chunks.next() {
+                        let frame_kind = if chunks.peek().is_some() {
+                            FrameKind::Continuation
+                        } else {
+                            FrameKind::Final
+                        };
+
+                        bytes.clear();
+                        bytes.extend_from_slice(
+                            (FRAME_HEADER_LEN + chunk.len()).to_string().as_bytes(),
+                        );
+                        bytes.push(b':');
+                        bytes.push(command);
+                        bytes.push(frame_kind.as_byte());
+                        bytes.extend_from_slice(&stream_id.to_le_bytes());
+                        bytes.extend_from_slice(chunk);
+                        bytes.push(b',');
+
+                        if let Err(error) = writer.write_all(&bytes).await {
+                            break 'writer Err(error);
+                        }
+                    }
+                    metrics.record_sent(payload.len());
+                };
+
+                if let Err(error) = &result {
+                    error!("channel writer task ending: {}", error);
                 }
 
-                io::Result::Ok(())
+                result
             })
             .detach();
 
         sender
     };
 
-    (sender, receiver)
+    Channel {
+        sender,
+        receiver,
+        metrics,
+    }
+}
+
+/// A channel pair (control or payload channel), with a lightweight metrics hook reporting
+/// throughput and queue saturation, see [`Channel::metrics`].
+pub struct Channel {
+    pub sender: Sender<ChannelRequest>,
+    pub receiver: Receiver<ChannelMessage>,
+    metrics: ChannelMetrics,
+}
+
+impl Channel {
+    /// Snapshot of this channel's throughput and queue saturation.
+    pub fn metrics(&self) -> ChannelMetricsSnapshot {
+        self.metrics
+            .snapshot(self.sender.len(), self.receiver.len())
+    }
+}
+
+/// A handle to a spawned worker process, kept around after its pipes have been handed off to
+/// [`create_channel_pair`]. Exposes just enough to manage its lifecycle: a background task owns
+/// the underlying [`async_process::Child`] to wait on its exit status (see [`ExitHandle`]), so
+/// this only needs the pid to request termination.
+pub struct WorkerProcess {
+    pid: Pid,
+}
+
+impl WorkerProcess {
+    /// Sends `SIGKILL` to the worker, forcing it to terminate immediately.
+    pub fn kill(&self) -> io::Result<()> {
+        signal::kill(self.pid, Signal::SIGKILL)
+            .map_err(|errno| io::Error::from_raw_os_error(errno as i32))
+    }
+}
+
+/// How a worker process ended, distinguishing a clean exit from a crash.
+#[derive(Debug, Clone, Copy)]
+pub enum WorkerExitStatus {
+    /// Exited with a zero status.
+    Exited(ExitStatus),
+    /// Exited with a non-zero status or was killed by a signal.
+    Died(ExitStatus),
+}
+
+/// Resolves once the worker process exits, letting callers distinguish a clean shutdown from a
+/// crash (and, in the latter case, implement automatic respawn) instead of only noticing the
+/// channel went quiet.
+pub struct ExitHandle {
+    receiver: async_oneshot::Receiver<io::Result<ExitStatus>>,
+}
+
+impl ExitHandle {
+    /// Waits for the worker process to exit, returning its status.
+    pub async fn wait(self) -> io::Result<WorkerExitStatus> {
+        let status = self.receiver.await.unwrap_or_else(|_| {
+            Err(io::Error::new(io::ErrorKind::Other, "exit watcher dropped"))
+        })?;
+
+        Ok(if status.success() {
+            WorkerExitStatus::Exited(status)
+        } else {
+            WorkerExitStatus::Died(status)
+        })
+    }
 }
 
 pub struct SpawnResult {
-    pub child: Child,
-    pub channel: (Sender<Vec<u8>>, Receiver<ChannelMessage>),
-    pub payload_channel: (Sender<Vec<u8>>, Receiver<ChannelMessage>),
+    pub child: WorkerProcess,
+    pub channel: Channel,
+    pub payload_channel: Channel,
+    pub exit: ExitHandle,
 }
 
 fn create_pipe() -> (RawFd, RawFd) {
@@ -134,6 +666,43 @@ fn create_pipe() -> (RawFd, RawFd) {
 pub fn spawn_with_worker_channels(
     executor: &Executor,
     command: &mut Command,
+) -> io::Result<SpawnResult> {
+    spawn_with_worker_channels_with_compression(
+        executor,
+        command,
+        PayloadCompressionConfig::default(),
+    )
+}
+
+/// Like [`spawn_with_worker_channels`], but additionally negotiates transparent compression of
+/// the bodies crossing the channel and payload channel pipes. Frames are only marked compressed
+/// when `compression` has a compressor configured, so leaving it at its default keeps the plain,
+/// uncompressed path workers have always spoken.
+pub fn spawn_with_worker_channels_with_compression(
+    executor: &Executor,
+    command: &mut Command,
+    compression: PayloadCompressionConfig,
+) -> io::Result<SpawnResult> {
+    spawn_with_worker_channels_with_compression_and_capacities(
+        executor,
+        command,
+        compression,
+        ChannelCapacities::default(),
+        ChannelCapacities::default(),
+    )
+}
+
+/// Like [`spawn_with_worker_channels_with_compression`], but additionally configures the bounded
+/// queue capacities of the control channel and payload channel independently. A single-slot
+/// bound serializes the worker to one in-flight message at a time; raising `incoming`/`outgoing`
+/// lets bursts queue up instead of head-of-line blocking between the two channels. See
+/// [`Channel::metrics`] for visibility into whether that's actually the bottleneck.
+pub fn spawn_with_worker_channels_with_compression_and_capacities(
+    executor: &Executor,
+    command: &mut Command,
+    compression: PayloadCompressionConfig,
+    channel_capacities: ChannelCapacities,
+    payload_channel_capacities: ChannelCapacities,
 ) -> io::Result<SpawnResult> {
     let (producer_fd_read, producer_fd_write) = create_pipe();
     let (consumer_fd_read, consumer_fd_write) = create_pipe();
@@ -176,7 +745,7 @@ pub fn spawn_with_worker_channels(
     let consumer_payload_file: AsyncFile =
         unsafe { StdFile::from_raw_fd(consumer_payload_fd_read) }.into();
 
-    let child = command.spawn()?;
+    let child: Child = command.spawn()?;
 
     // Unused in parent
     unistd::close(producer_fd_read).expect("Failed to close fd");
@@ -184,13 +753,34 @@ pub fn spawn_with_worker_channels(
     unistd::close(producer_payload_fd_read).expect("Failed to close fd");
     unistd::close(consumer_payload_fd_write).expect("Failed to close fd");
 
+    let pid = Pid::from_raw(child.id() as i32);
+    let (mut exit_sender, exit_receiver) = async_oneshot::oneshot();
+    executor
+        .spawn(async move {
+            let mut child = child;
+            let status = child.status().await;
+            let _ = exit_sender.send(status);
+        })
+        .detach();
+
     Ok(SpawnResult {
-        child,
-        channel: create_channel_pair(&executor, consumer_file, producer_file),
+        child: WorkerProcess { pid },
+        channel: create_channel_pair(
+            &executor,
+            consumer_file,
+            producer_file,
+            compression.clone(),
+            channel_capacities,
+        ),
         payload_channel: create_channel_pair(
             &executor,
             consumer_payload_file,
             producer_payload_file,
+            compression,
+            payload_channel_capacities,
         ),
+        exit: ExitHandle {
+            receiver: exit_receiver,
+        },
     })
-}
\ No newline at end of file
+}
@@ -1,19 +1,26 @@
 use crate::messages::{Notification, Request};
+use crate::worker::channel_codec::{ChannelCodec, JsonCodec};
 use crate::worker::common::EventHandlers;
+use crate::worker::payload_compression::PayloadCompressionConfig;
 use crate::worker::{RequestError, SubscriptionHandler};
 use async_executor::Executor;
-use async_fs::File;
 use async_mutex::Mutex;
 use bytes::Bytes;
 use futures_lite::io::BufReader;
-use futures_lite::{future, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+use futures_lite::{
+    future, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, Stream,
+};
 use log::*;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::fmt::Debug;
+use std::future::Future;
 use std::io;
-use std::sync::Arc;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
 use std::time::Duration;
 use thiserror::Error;
 
@@ -45,6 +52,9 @@ enum PayloadChannelReceiveMessage {
         id: u32,
         accepted: bool,
         data: Option<Value>,
+        /// Set by the worker on every chunk of a streamed response except the last one.
+        #[serde(default)]
+        more: bool,
     },
     ResponseError {
         id: u32,
@@ -58,8 +68,17 @@ enum PayloadChannelReceiveMessage {
     Internal(InternalMessage),
 }
 
-fn deserialize_message(bytes: &[u8]) -> PayloadChannelReceiveMessage {
-    match serde_json::from_slice(bytes) {
+/// The codec a [`PayloadChannel`] currently encodes/decodes message bodies with, swapped in place
+/// once [`PayloadChannel::negotiate_codec`] confirms the worker has agreed to a non-default one.
+type SharedCodec = Arc<RwLock<Arc<dyn ChannelCodec>>>;
+
+fn deserialize_message(codec: &dyn ChannelCodec, bytes: &[u8]) -> PayloadChannelReceiveMessage {
+    let parsed = codec
+        .decode(bytes)
+        .map_err(|error| error.to_string())
+        .and_then(|value| serde_json::from_value(value).map_err(|error| error.to_string()));
+
+    match parsed {
         Ok(message) => message,
         Err(error) => {
             warn!("Failed to deserialize message: {}", error);
@@ -88,10 +107,202 @@ struct ResponseError {
 
 type Response<T> = Result<Option<T>, ResponseError>;
 
+/// A pending request is either resolved exactly once, or kept open to forward a sequence of
+/// chunks until the worker marks one as final.
+enum RequestHandler {
+    Single(async_oneshot::Sender<Response<Value>>),
+    Stream(async_channel::Sender<Response<Value>>),
+}
+
+/// A stream of chunked responses to a single [`PayloadChannel::request_stream`] call.
+///
+/// Dropping the stream before it is exhausted removes the pending handler and notifies the
+/// worker with a `payloadChannelRequestCancelled` notification so it can stop producing chunks
+/// nobody is listening to anymore; any chunks already in flight when the notification arrives are
+/// discarded.
+pub(crate) struct RequestStream {
+    id: u32,
+    timeout: Duration,
+    timer: async_io::Timer,
+    receiver: async_channel::Receiver<Response<Value>>,
+    channel: PayloadChannel,
+}
+
+impl Stream for RequestStream {
+    type Item = Result<Value, RequestError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        match Pin::new(&mut self.receiver).poll_next(cx) {
+            Poll::Ready(Some(Ok(data))) => {
+                self.timer.set_after(self.timeout);
+                Poll::Ready(Some(Ok(data.unwrap_or_default())))
+            }
+            Poll::Ready(Some(Err(ResponseError { reason }))) => {
+                Poll::Ready(Some(Err(RequestError::Response { reason })))
+            }
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => match Pin::new(&mut self.timer).poll(cx) {
+                Poll::Ready(_) => {
+                    if let Some(mut requests_container) =
+                        self.channel.inner.requests_container.try_lock()
+                    {
+                        if requests_container.handlers.remove(&self.id).is_some() {
+                            self.channel.inner.metrics.record_timed_out();
+                        }
+                    }
+                    Poll::Ready(Some(Err(RequestError::TimedOut)))
+                }
+                Poll::Pending => Poll::Pending,
+            },
+        }
+    }
+}
+
+impl Drop for RequestStream {
+    fn drop(&mut self) {
+        self.receiver.close();
+
+        let channel = self.channel.clone();
+        let id = self.id;
+        let executor = Arc::clone(&channel.inner.executor);
+
+        // Removing the handler needs the async container lock, and telling the worker to stop
+        // producing chunks for a request nobody is listening to anymore needs a round trip to the
+        // worker — spawn both instead of blocking the thread running `Drop`, which could deadlock
+        // an executor whose only thread is the one tearing this stream down.
+        executor
+            .spawn(async move {
+                let removed = channel
+                    .inner
+                    .requests_container
+                    .lock()
+                    .await
+                    .handlers
+                    .remove(&id)
+                    .is_some();
+
+                if removed {
+                    channel.inner.metrics.record_dropped();
+                    let _ = channel
+                        .notify_internal(
+                            "payloadChannelRequestCancelled",
+                            serde_json::json!({ "id": id }),
+                            Bytes::new(),
+                        )
+                        .await;
+                }
+            })
+            .detach();
+    }
+}
+
 #[derive(Default)]
 struct RequestsContainer {
     next_id: u32,
-    handlers: HashMap<u32, async_oneshot::Sender<Response<Value>>>,
+    handlers: HashMap<u32, RequestHandler>,
+}
+
+/// Configuration for [`PayloadChannel::new`]'s request/response plumbing: how many messages may
+/// be buffered between the reader/writer tasks and the rest of the channel, and how long a
+/// request waits for a response before giving up.
+///
+/// The timeout grows with the number of requests already queued ahead of a new one
+/// (`base_timeout + per_queued_request_timeout * queue_len`), so a backlog of slow requests
+/// doesn't cause later ones to time out prematurely.
+#[derive(Debug, Clone)]
+pub(crate) struct PayloadChannelConfig {
+    pub(crate) channel_capacity: usize,
+    pub(crate) base_timeout: Duration,
+    pub(crate) per_queued_request_timeout: Duration,
+}
+
+impl Default for PayloadChannelConfig {
+    fn default() -> Self {
+        Self {
+            channel_capacity: 1,
+            base_timeout: Duration::from_secs(15),
+            per_queued_request_timeout: Duration::from_millis(100),
+        }
+    }
+}
+
+impl PayloadChannelConfig {
+    fn timeout_for(&self, queue_len: usize) -> Duration {
+        self.base_timeout + self.per_queued_request_timeout * queue_len as u32
+    }
+}
+
+#[derive(Debug, Default)]
+struct ChannelMetricsInner {
+    in_flight_requests: AtomicUsize,
+    timed_out_requests: AtomicU64,
+    dropped_requests: AtomicU64,
+    high_water_queue_depth: AtomicUsize,
+}
+
+/// Lock-free counters tracking [`PayloadChannel`] request-queue saturation, so buffer sizes and
+/// alerting thresholds can be chosen from observed behavior instead of by waiting to see
+/// [`RequestError::TimedOut`].
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChannelMetrics {
+    inner: Arc<ChannelMetricsInner>,
+}
+
+impl ChannelMetrics {
+    fn record_enqueued(&self, queue_len_after_insert: usize) {
+        self.inner
+            .in_flight_requests
+            .fetch_add(1, Ordering::Relaxed);
+        self.inner
+            .high_water_queue_depth
+            .fetch_max(queue_len_after_insert, Ordering::Relaxed);
+    }
+
+    fn record_completed(&self) {
+        self.inner
+            .in_flight_requests
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+
+    fn record_timed_out(&self) {
+        self.inner
+            .in_flight_requests
+            .fetch_sub(1, Ordering::Relaxed);
+        self.inner
+            .timed_out_requests
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record_dropped(&self) {
+        self.inner
+            .in_flight_requests
+            .fetch_sub(1, Ordering::Relaxed);
+        self.inner.dropped_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Snapshot of the current counters.
+    pub(crate) fn snapshot(&self) -> PayloadChannelMetrics {
+        PayloadChannelMetrics {
+            in_flight_requests: self.inner.in_flight_requests.load(Ordering::Relaxed),
+            timed_out_requests: self.inner.timed_out_requests.load(Ordering::Relaxed),
+            dropped_requests: self.inner.dropped_requests.load(Ordering::Relaxed),
+            high_water_queue_depth: self.inner.high_water_queue_depth.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Point-in-time view of [`PayloadChannel`] queue saturation, see [`PayloadChannel::metrics`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PayloadChannelMetrics {
+    /// Requests sent to the worker that haven't resolved, failed or timed out yet.
+    pub(crate) in_flight_requests: usize,
+    /// Total requests that hit their timeout without a response.
+    pub(crate) timed_out_requests: u64,
+    /// Total request streams dropped by the caller before their final chunk arrived. A
+    /// single-response request whose future is dropped isn't counted here.
+    pub(crate) dropped_requests: u64,
+    /// Highest number of requests in flight at once, counting the one just sent.
+    pub(crate) high_water_queue_depth: usize,
 }
 
 struct Inner {
@@ -99,6 +310,12 @@ struct Inner {
     internal_message_receiver: async_channel::Receiver<InternalMessage>,
     requests_container: Arc<Mutex<RequestsContainer>>,
     event_handlers: EventHandlers<NotificationMessage>,
+    codec: SharedCodec,
+    metrics: ChannelMetrics,
+    config: PayloadChannelConfig,
+    // Kept around so a dropped `RequestStream` can spawn its worker-side cancellation instead of
+    // blocking the thread running `Drop` on the async lock it needs.
+    executor: Arc<Executor<'static>>,
 }
 
 impl Drop for Inner {
@@ -114,14 +331,91 @@ pub(crate) struct PayloadChannel {
 }
 
 impl PayloadChannel {
-    pub(super) fn new(executor: Arc<Executor<'static>>, reader: File, mut writer: File) -> Self {
+    pub(super) async fn new(
+        executor: Arc<Executor<'static>>,
+        reader: impl AsyncRead + Unpin + Send + 'static,
+        writer: impl AsyncWrite + Unpin + Send + 'static,
+    ) -> Self {
+        Self::new_with_codec_and_compression_and_config(
+            executor,
+            reader,
+            writer,
+            None,
+            PayloadCompressionConfig::default(),
+            PayloadChannelConfig::default(),
+        )
+        .await
+    }
+
+    /// Like [`PayloadChannel::new`], but negotiates `codec` with the worker instead of keeping
+    /// the JSON default every worker understands without negotiation. The channel speaks JSON
+    /// until [`PayloadChannel::negotiate_codec`] confirms the worker has agreed to `codec_name`;
+    /// if the worker rejects the handshake or never responds, the channel keeps speaking JSON
+    /// rather than switching to a codec the worker never agreed to.
+    pub(super) async fn new_with_codec(
+        executor: Arc<Executor<'static>>,
+        reader: impl AsyncRead + Unpin + Send + 'static,
+        writer: impl AsyncWrite + Unpin + Send + 'static,
+        codec_name: &'static str,
+        codec: Arc<dyn ChannelCodec>,
+    ) -> Self {
+        Self::new_with_codec_and_compression(
+            executor,
+            reader,
+            writer,
+            codec_name,
+            codec,
+            PayloadCompressionConfig::default(),
+        )
+        .await
+    }
+
+    /// Like [`PayloadChannel::new_with_codec`], but additionally negotiates transparent
+    /// compression of the `payload: Bytes` that rides alongside each message.
+    pub(super) async fn new_with_codec_and_compression(
+        executor: Arc<Executor<'static>>,
+        reader: impl AsyncRead + Unpin + Send + 'static,
+        writer: impl AsyncWrite + Unpin + Send + 'static,
+        codec_name: &'static str,
+        codec: Arc<dyn ChannelCodec>,
+        compression: PayloadCompressionConfig,
+    ) -> Self {
+        Self::new_with_codec_and_compression_and_config(
+            executor,
+            reader,
+            writer,
+            Some((codec_name, codec)),
+            compression,
+            PayloadChannelConfig::default(),
+        )
+        .await
+    }
+
+    /// Like [`PayloadChannel::new_with_codec_and_compression`], but additionally allows tuning
+    /// the internal channel capacity and request timeout, see [`PayloadChannelConfig`].
+    pub(super) async fn new_with_codec_and_compression_and_config(
+        executor: Arc<Executor<'static>>,
+        reader: impl AsyncRead + Unpin + Send + 'static,
+        mut writer: impl AsyncWrite + Unpin + Send + 'static,
+        codec_negotiation: Option<(&'static str, Arc<dyn ChannelCodec>)>,
+        compression: PayloadCompressionConfig,
+        config: PayloadChannelConfig,
+    ) -> Self {
+        // The channel always starts on JSON, the one encoding every worker understands without
+        // prior agreement; `codec_negotiation`, if any, is only switched to once the worker has
+        // actually confirmed it in `negotiate_codec` below.
+        let codec: SharedCodec = Arc::new(RwLock::new(Arc::new(JsonCodec)));
         let requests_container = Arc::<Mutex<RequestsContainer>>::default();
         let event_handlers = EventHandlers::new(Arc::clone(&executor));
+        let metrics = ChannelMetrics::default();
 
         let internal_message_receiver = {
             let requests_container = Arc::clone(&requests_container);
             let event_handlers = event_handlers.clone();
-            let (sender, receiver) = async_channel::bounded(1);
+            let codec = Arc::clone(&codec);
+            let compression = compression.clone();
+            let metrics = metrics.clone();
+            let (sender, receiver) = async_channel::bounded(config.channel_capacity);
 
             executor
                 .spawn(async move {
@@ -155,21 +449,57 @@ impl PayloadChannel {
                             String::from_utf8_lossy(&bytes[..length]),
                         );
 
-                        match deserialize_message(&bytes[..length]) {
+                        match deserialize_message(codec.read().unwrap().as_ref(), &bytes[..length])
+                        {
                             PayloadChannelReceiveMessage::ResponseSuccess {
                                 id,
                                 accepted: _,
                                 data,
+                                more,
                             } => {
-                                let sender = requests_container.lock().await.handlers.remove(&id);
-                                if let Some(sender) = sender {
-                                    let _ = sender.send(Ok(data));
+                                if more {
+                                    // Intermediate chunk of a streamed response: keep the handler
+                                    // registered and reset nothing here, the per-request timer is
+                                    // reset by `RequestStream::poll_next` when it yields the chunk.
+                                    let sender =
+                                        match requests_container.lock().await.handlers.get(&id) {
+                                            Some(RequestHandler::Stream(sender)) => {
+                                                Some(sender.clone())
+                                            }
+                                            Some(RequestHandler::Single(_)) | None => None,
+                                        };
+                                    match sender {
+                                        Some(sender) => {
+                                            let _ = sender.send(Ok(data)).await;
+                                        }
+                                        None => {
+                                            warn!(
+                                                "received streamed chunk that does not match any \
+                                                 sent request [id:{}]",
+                                                id,
+                                            );
+                                        }
+                                    }
                                 } else {
-                                    warn!(
-                                        "received success response does not match any sent request \
-                                         [id:{}]",
-                                        id,
-                                    );
+                                    let handler =
+                                        requests_container.lock().await.handlers.remove(&id);
+                                    match handler {
+                                        Some(RequestHandler::Single(sender)) => {
+                                            metrics.record_completed();
+                                            let _ = sender.send(Ok(data));
+                                        }
+                                        Some(RequestHandler::Stream(sender)) => {
+                                            metrics.record_completed();
+                                            let _ = sender.send(Ok(data)).await;
+                                        }
+                                        None => {
+                                            warn!(
+                                                "received success response does not match any \
+                                                 sent request [id:{}]",
+                                                id,
+                                            );
+                                        }
+                                    }
                                 }
                             }
                             PayloadChannelReceiveMessage::ResponseError {
@@ -177,15 +507,23 @@ impl PayloadChannel {
                                 error: _,
                                 reason,
                             } => {
-                                let sender = requests_container.lock().await.handlers.remove(&id);
-                                if let Some(sender) = sender {
-                                    let _ = sender.send(Err(ResponseError { reason }));
-                                } else {
-                                    warn!(
-                                        "received error response does not match any sent request \
-                                        [id:{}]",
-                                        id,
-                                    );
+                                let handler = requests_container.lock().await.handlers.remove(&id);
+                                match handler {
+                                    Some(RequestHandler::Single(sender)) => {
+                                        metrics.record_completed();
+                                        let _ = sender.send(Err(ResponseError { reason }));
+                                    }
+                                    Some(RequestHandler::Stream(sender)) => {
+                                        metrics.record_completed();
+                                        let _ = sender.send(Err(ResponseError { reason })).await;
+                                    }
+                                    None => {
+                                        warn!(
+                                            "received error response does not match any sent \
+                                            request [id:{}]",
+                                            id,
+                                        );
+                                    }
                                 }
                             }
                             PayloadChannelReceiveMessage::Notification(notification) => {
@@ -218,7 +556,16 @@ impl PayloadChannel {
 
                                 trace!("received notification payload of {} bytes", length);
 
-                                let payload = Bytes::copy_from_slice(&bytes[..length]);
+                                let payload = match compression.decode(&bytes[..length]) {
+                                    Ok(payload) => Bytes::from(payload),
+                                    Err(error) => {
+                                        warn!(
+                                            "Failed to decompress notification payload: {}",
+                                            error
+                                        );
+                                        Bytes::new()
+                                    }
+                                };
 
                                 match target_id {
                                     Some(target_id) => {
@@ -258,7 +605,8 @@ impl PayloadChannel {
         };
 
         let sender = {
-            let (sender, receiver) = async_channel::bounded::<MessageWithPayload>(1);
+            let (sender, receiver) =
+                async_channel::bounded::<MessageWithPayload>(config.channel_capacity);
 
             executor
                 .spawn(async move {
@@ -272,10 +620,12 @@ impl PayloadChannel {
 
                         writer.write_all(&bytes).await?;
 
+                        let payload = compression.encode(&message.payload);
+
                         bytes.clear();
-                        bytes.extend_from_slice(message.payload.len().to_string().as_bytes());
+                        bytes.extend_from_slice(payload.len().to_string().as_bytes());
                         bytes.push(b':');
-                        bytes.extend_from_slice(&message.payload);
+                        bytes.extend_from_slice(&payload);
                         bytes.push(b',');
 
                         writer.write_all(&bytes).await?;
@@ -293,9 +643,51 @@ impl PayloadChannel {
             internal_message_receiver,
             requests_container,
             event_handlers,
+            codec,
+            metrics,
+            config,
+            executor,
         });
 
-        Self { inner }
+        let channel = Self { inner };
+
+        if let Some((codec_name, codec)) = codec_negotiation {
+            channel.negotiate_codec(codec_name, codec).await;
+        }
+
+        channel
+    }
+
+    /// Asks the worker, over the JSON codec every worker speaks by default, to switch the payload
+    /// channel's wire encoding to `codec_name`. Only swaps this channel's codec to `codec` once
+    /// the worker has actually acknowledged the request; on failure, logs a warning and leaves
+    /// the channel on JSON rather than speaking a codec the worker never agreed to.
+    async fn negotiate_codec(&self, codec_name: &'static str, codec: Arc<dyn ChannelCodec>) {
+        let result = self
+            .request_internal(
+                "setPayloadChannelCodec",
+                serde_json::json!({ "codec": codec_name }),
+                Bytes::new(),
+            )
+            .await;
+
+        match result {
+            Ok(_) => {
+                *self.inner.codec.write().unwrap() = codec;
+                debug!("negotiated payload channel codec [codec:{}]", codec_name);
+            }
+            Err(error) => {
+                warn!(
+                    "failed to negotiate payload channel codec [codec:{}]: {}, keeping JSON",
+                    codec_name, error,
+                );
+            }
+        }
+    }
+
+    /// Snapshot of the request queue's saturation, see [`PayloadChannelMetrics`].
+    pub(crate) fn metrics(&self) -> PayloadChannelMetrics {
+        self.inner.metrics.snapshot()
     }
 
     pub(super) fn get_internal_message_receiver(&self) -> async_channel::Receiver<InternalMessage> {
@@ -324,6 +716,26 @@ impl PayloadChannel {
         })
     }
 
+    /// Like [`PayloadChannel::request`], but for requests whose response arrives as a sequence of
+    /// chunks rather than a single value (e.g. a large dump or a live stats feed tied to one
+    /// request id). Each chunk is surfaced as a `Stream` item; the per-request timeout is reset
+    /// whenever a chunk is received, and dropping the stream cancels the request.
+    pub(crate) async fn request_stream<R>(
+        &self,
+        request: R,
+        payload: Bytes,
+    ) -> Result<RequestStream, RequestError>
+    where
+        R: Request,
+    {
+        self.request_stream_internal(
+            request.as_method(),
+            serde_json::to_value(request).unwrap(),
+            payload,
+        )
+        .await
+    }
+
     pub(crate) async fn notify<N>(
         &self,
         notification: N,
@@ -378,25 +790,32 @@ impl PayloadChannel {
             queue_len = requests_container.handlers.len();
 
             requests_container.next_id = requests_container.next_id.wrapping_add(1);
-            requests_container.handlers.insert(id, result_sender);
+            requests_container
+                .handlers
+                .insert(id, RequestHandler::Single(result_sender));
         }
+        self.inner.metrics.record_enqueued(queue_len + 1);
 
         debug!("request() [method:{}, id:{}]", method, id);
 
-        let serialized_message = serde_json::to_vec(&RequestMessagePrivate {
-            id,
-            method,
-            message,
-        })
-        .unwrap();
+        let serialized_message = self.inner.codec.read().unwrap().encode(
+            &serde_json::to_value(&RequestMessagePrivate {
+                id,
+                method,
+                message,
+            })
+            .unwrap(),
+        );
 
         if serialized_message.len() > NS_PAYLOAD_MAX_LEN {
             requests_container.lock().await.handlers.remove(&id);
+            self.inner.metrics.record_completed();
             return Err(RequestError::MessageTooLong);
         }
 
         if payload.len() > NS_PAYLOAD_MAX_LEN {
             requests_container.lock().await.handlers.remove(&id);
+            self.inner.metrics.record_completed();
             return Err(RequestError::PayloadTooLong);
         }
 
@@ -409,6 +828,9 @@ impl PayloadChannel {
             .await
             .map_err(|_| RequestError::ChannelClosed {})?;
 
+        let timeout = self.inner.config.timeout_for(queue_len);
+        let metrics = self.inner.metrics.clone();
+
         let result = future::or(
             async move {
                 result_receiver
@@ -416,12 +838,17 @@ impl PayloadChannel {
                     .map_err(|_| RequestError::ChannelClosed {})
             },
             async move {
-                async_io::Timer::after(Duration::from_millis(
-                    (1000.0 * (15.0 + (0.1 * queue_len as f64))).round() as u64,
-                ))
-                .await;
+                async_io::Timer::after(timeout).await;
 
-                requests_container.lock().await.handlers.remove(&id);
+                if requests_container
+                    .lock()
+                    .await
+                    .handlers
+                    .remove(&id)
+                    .is_some()
+                {
+                    metrics.record_timed_out();
+                }
 
                 Err(RequestError::TimedOut)
             },
@@ -441,6 +868,82 @@ impl PayloadChannel {
         }
     }
 
+    /// Non-generic method to avoid significant duplication in final binary
+    async fn request_stream_internal(
+        &self,
+        method: &'static str,
+        message: Value,
+        payload: Bytes,
+    ) -> Result<RequestStream, RequestError> {
+        #[derive(Debug, Serialize)]
+        struct RequestMessagePrivate {
+            id: u32,
+            method: &'static str,
+            #[serde(flatten)]
+            message: Value,
+        }
+
+        let id;
+        let queue_len;
+        let (chunk_sender, chunk_receiver) = async_channel::unbounded();
+        let requests_container = Arc::clone(&self.inner.requests_container);
+
+        {
+            let mut requests_container = requests_container.lock().await;
+
+            id = requests_container.next_id;
+            queue_len = requests_container.handlers.len();
+
+            requests_container.next_id = requests_container.next_id.wrapping_add(1);
+            requests_container
+                .handlers
+                .insert(id, RequestHandler::Stream(chunk_sender));
+        }
+        self.inner.metrics.record_enqueued(queue_len + 1);
+
+        debug!("request_stream() [method:{}, id:{}]", method, id);
+
+        let serialized_message = self.inner.codec.read().unwrap().encode(
+            &serde_json::to_value(&RequestMessagePrivate {
+                id,
+                method,
+                message,
+            })
+            .unwrap(),
+        );
+
+        if serialized_message.len() > NS_PAYLOAD_MAX_LEN {
+            requests_container.lock().await.handlers.remove(&id);
+            self.inner.metrics.record_completed();
+            return Err(RequestError::MessageTooLong);
+        }
+
+        if payload.len() > NS_PAYLOAD_MAX_LEN {
+            requests_container.lock().await.handlers.remove(&id);
+            self.inner.metrics.record_completed();
+            return Err(RequestError::PayloadTooLong);
+        }
+
+        self.inner
+            .sender
+            .send(MessageWithPayload {
+                message: serialized_message,
+                payload,
+            })
+            .await
+            .map_err(|_| RequestError::ChannelClosed {})?;
+
+        let timeout = self.inner.config.timeout_for(queue_len);
+
+        Ok(RequestStream {
+            id,
+            timeout,
+            timer: async_io::Timer::after(timeout),
+            receiver: chunk_receiver,
+            channel: self.clone(),
+        })
+    }
+
     /// Non-generic method to avoid significant duplication in final binary
     async fn notify_internal(
         &self,
@@ -457,11 +960,13 @@ impl PayloadChannel {
 
         debug!("notify() [event:{}]", event);
 
-        let serialized_notification = serde_json::to_vec(&NotificationMessagePrivate {
-            event,
-            notification,
-        })
-        .unwrap();
+        let serialized_notification = self.inner.codec.read().unwrap().encode(
+            &serde_json::to_value(&NotificationMessagePrivate {
+                event,
+                notification,
+            })
+            .unwrap(),
+        );
 
         if serialized_notification.len() > NS_PAYLOAD_MAX_LEN {
             return Err(NotificationError::MessageTooLong);
@@ -480,4 +985,4 @@ impl PayloadChannel {
             .await
             .map_err(|_| NotificationError::ChannelClosed {})
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Error produced while decompressing a payload off the wire.
+#[derive(Debug, Error)]
+pub(crate) enum DecompressionError {
+    #[error("Failed to decompress payload: {0}")]
+    Failed(String),
+}
+
+/// Tag written as the first byte of a payload once compression is negotiated for the channel,
+/// so the reader knows whether the remaining bytes need to be inflated.
+pub(crate) const PAYLOAD_ENCODING_RAW: u8 = 0;
+pub(crate) const PAYLOAD_ENCODING_COMPRESSED: u8 = 1;
+
+/// Compresses/decompresses the `payload: Bytes` that rides alongside channel messages.
+pub(crate) trait PayloadCompressor: Send + Sync {
+    fn compress(&self, payload: &[u8]) -> Vec<u8>;
+
+    fn decompress(&self, payload: &[u8]) -> Result<Vec<u8>, DecompressionError>;
+}
+
+/// Fast streaming codec suitable for bursty data-heavy traffic (DataChannel SCTP payloads,
+/// probation packets, large dumps). Opt-in via a cargo feature since it pulls in a compression
+/// dependency that most deployments don't need.
+#[cfg(feature = "payload-compression")]
+#[derive(Default)]
+pub(crate) struct DeflateCompressor;
+
+#[cfg(feature = "payload-compression")]
+impl PayloadCompressor for DeflateCompressor {
+    fn compress(&self, payload: &[u8]) -> Vec<u8> {
+        use flate2::write::DeflateEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::fast());
+        encoder
+            .write_all(payload)
+            .expect("Writing to an in-memory buffer never fails");
+        encoder
+            .finish()
+            .expect("Writing to an in-memory buffer never fails")
+    }
+
+    fn decompress(&self, payload: &[u8]) -> Result<Vec<u8>, DecompressionError> {
+        use flate2::read::DeflateDecoder;
+        use std::io::Read;
+
+        let mut decoder = DeflateDecoder::new(payload);
+        let mut decompressed = Vec::new();
+        decoder
+            .read_to_end(&mut decompressed)
+            .map_err(|error| DecompressionError::Failed(error.to_string()))?;
+        Ok(decompressed)
+    }
+}
+
+/// Configuration for optional transparent compression of channel payloads, negotiated once at
+/// [`super::payload_channel::PayloadChannel::new`] time and held fixed for the channel's lifetime.
+///
+/// When `compressor` is `None` (the default) the wire format is unchanged from a plain channel;
+/// payloads at or below `threshold` bytes are always sent raw to avoid penalizing small control
+/// messages.
+#[derive(Clone, Default)]
+pub(crate) struct PayloadCompressionConfig {
+    pub(crate) compressor: Option<Arc<dyn PayloadCompressor>>,
+    pub(crate) threshold: usize,
+}
+
+impl PayloadCompressionConfig {
+    /// Tags and, if above `threshold`, compresses `payload` for the wire. Returns `payload`
+    /// unchanged if compression isn't negotiated for this channel.
+    pub(crate) fn encode(&self, payload: &[u8]) -> Vec<u8> {
+        let compressor = match &self.compressor {
+            Some(compressor) => compressor,
+            None => return payload.to_vec(),
+        };
+
+        let mut encoded = Vec::with_capacity(payload.len() + 1);
+        if payload.len() > self.threshold {
+            encoded.push(PAYLOAD_ENCODING_COMPRESSED);
+            encoded.extend_from_slice(&compressor.compress(payload));
+        } else {
+            encoded.push(PAYLOAD_ENCODING_RAW);
+            encoded.extend_from_slice(payload);
+        }
+        encoded
+    }
+
+    /// Reverses [`PayloadCompressionConfig::encode`]. Returns `payload` unchanged if compression
+    /// isn't negotiated for this channel.
+    pub(crate) fn decode(&self, payload: &[u8]) -> Result<Vec<u8>, DecompressionError> {
+        let compressor = match &self.compressor {
+            Some(compressor) => compressor,
+            None => return Ok(payload.to_vec()),
+        };
+
+        match payload.split_first() {
+            Some((&PAYLOAD_ENCODING_COMPRESSED, rest)) => compressor.decompress(rest),
+            Some((&PAYLOAD_ENCODING_RAW, rest)) => Ok(rest.to_vec()),
+            Some((tag, _)) => Err(DecompressionError::Failed(format!(
+                "Unknown payload encoding tag {}",
+                tag,
+            ))),
+            None => Ok(Vec::new()),
+        }
+    }
+}